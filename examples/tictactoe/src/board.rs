@@ -1,39 +1,35 @@
 use crate::game::SquareValue;
 use crate::square::Square;
-use crate::square::SquareProps;
-use review::children;
 use review::component;
-use review::ElementBuilder;
-use review::Tag::Div;
-use std::rc::Rc;
+use review::html;
+use review::Callback;
 
-pub struct BoardProps {
-    pub squares: [Option<SquareValue>; 9],
-    pub on_click: Rc<dyn Fn(usize)>,
-}
-
-#[component(Board)]
-pub fn board(props: &BoardProps) -> VNode {
+#[component]
+pub fn board(squares: [Option<SquareValue>; 9], on_click: Callback<usize>) -> VNode {
     let square = |index: usize| {
-        let on_click = props.on_click.clone();
+        let value = match squares.get(index) {
+            Some(v) => *v,
+            _ => None,
+        };
 
-        Square(SquareProps {
-            value: match props.squares.get(index) {
-                Some(v) => *v,
-                _ => None,
-            },
-            on_click: Rc::new(move || on_click(index)),
-        })
+        html! { <Square value={value} on_click={on_click.reform(move |_| index)} /> }
     };
 
     let row = |start: usize| {
-        Div.with_attribute("class", "board-row")
-            .with_children(children!(
-                square(start),
-                square(start + 1),
-                square(start + 2)
-            ))
+        html! {
+            <div class="board-row">
+                {square(start)}
+                {square(start + 1)}
+                {square(start + 2)}
+            </div>
+        }
     };
 
-    Div.with_children(children!(row(0), row(3), row(6))).into()
+    html! {
+        <div>
+            {row(0)}
+            {row(3)}
+            {row(6)}
+        </div>
+    }
 }
@@ -1,9 +1,6 @@
-use crate::board::{Board, BoardProps};
-use review::EventType::OnClick;
-use review::Tag::{Button, Div, Li, Ul};
-use review::{callback, children, component, use_state, ElementBuilder, VNode};
+use crate::board::Board;
+use review::{callback, component, html, use_state, VNode};
 use std::fmt::Display;
-use std::rc::Rc;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum SquareValue {
@@ -50,7 +47,7 @@ fn calculate_winner(squares: &[Option<SquareValue>]) -> Option<SquareValue> {
 
 #[component(Game)]
 pub fn game() -> VNode {
-    let (game_state, set_game_state) = use_state(GameState {
+    let game_state = use_state(GameState {
         squares_history: vec![[None; 9]],
         step_index: 0,
         x_is_next: true,
@@ -68,16 +65,17 @@ pub fn game() -> VNode {
             } else {
                 "Go to game start".to_string()
             };
-            Li.with_child(Button.with_child(desc).with_event(OnClick, {
-                let game_state = game_state.clone();
-                let set_game_state = set_game_state.clone();
-                callback!(move || set_game_state(GameState {
-                    squares_history: game_state.squares_history.clone(),
-                    step_index: i,
-                    x_is_next: i % 2 == 0
-                }))
-            }))
-            .into()
+            let on_click = callback!([game_state] move || game_state.update(|prev| GameState {
+                squares_history: prev.squares_history.clone(),
+                step_index: i,
+                x_is_next: i % 2 == 0
+            }));
+
+            html! {
+                <li>
+                    <button on_click={on_click}>{desc}</button>
+                </li>
+            }
         })
         .collect();
 
@@ -98,40 +96,37 @@ pub fn game() -> VNode {
         .into(),
     };
 
-    let handle_click = {
-        let game_state = game_state;
-        move |index: usize| {
-            let mut new_square = current;
-            if calculate_winner(&new_square).is_none() {
-                if let Some(square @ None) = new_square.get_mut(index) {
-                    *square = Some(if game_state.x_is_next {
-                        SquareValue::X
-                    } else {
-                        SquareValue::O
-                    });
+    let handle_click = callback!(Callback, move |index: usize| {
+        let mut new_square = current;
+        if calculate_winner(&new_square).is_none() {
+            if let Some(square @ None) = new_square.get_mut(index) {
+                *square = Some(if game_state.x_is_next {
+                    SquareValue::X
+                } else {
+                    SquareValue::O
+                });
 
-                    let mut new_history =
-                        game_state.squares_history[0..game_state.step_index + 1].to_vec();
-                    new_history.push(new_square);
-                    set_game_state(GameState {
-                        squares_history: new_history,
-                        step_index: game_state.step_index + 1,
-                        x_is_next: !game_state.x_is_next,
-                    });
-                }
+                let mut new_history =
+                    game_state.squares_history[0..game_state.step_index + 1].to_vec();
+                new_history.push(new_square);
+                game_state.set(GameState {
+                    squares_history: new_history,
+                    step_index: game_state.step_index + 1,
+                    x_is_next: !game_state.x_is_next,
+                });
             }
         }
-    };
+    });
 
-    Div.with_attribute("class", "game")
-        .with_children(children!(
-            Div.with_attribute("game", "game-board")
-                .with_child(Board(BoardProps {
-                    squares: current,
-                    on_click: Rc::new(handle_click),
-                })),
-            Div.with_attribute("class", "game-info")
-                .with_children(children!(Div.with_child(status), Ul.with_children(moves)))
-        ))
-        .into()
+    html! {
+        <div class="game">
+            <div game="game-board">
+                <Board squares={current} on_click={handle_click} />
+            </div>
+            <div class="game-info">
+                <div>{status}</div>
+                <ul>{for moves}</ul>
+            </div>
+        </div>
+    }
 }
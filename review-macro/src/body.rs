@@ -4,13 +4,24 @@ use std::sync::{Arc, Mutex};
 use syn::spanned::Spanned;
 use syn::visit_mut::VisitMut;
 use syn::{
-    parse_quote_spanned, visit_mut, Expr, ExprCall, ExprClosure, ExprForLoop, ExprIf, ExprLoop,
-    ExprMatch, ExprWhile, Ident, Item,
+    parse_quote_spanned, visit_mut, Expr, ExprAsync, ExprCall, ExprClosure, ExprForLoop, ExprIf,
+    ExprLoop, ExprMatch, ExprReturn, ExprTry, ExprWhile, Ident, Item,
 };
 
+/// An expression that can leave the function before the hooks that follow it
+#[derive(Debug, Clone, Copy)]
+enum EarlyExit {
+    Return(Span),
+    Try(Span),
+}
+
 #[derive(Debug, Default)]
 pub struct BodyRewriter {
     branch_lock: Arc<Mutex<()>>,
+    /// The first early exit of the function, a hook after it could be skipped
+    early_exit: Option<EarlyExit>,
+    /// The depth of the closures and async blocks, whose exits don't leave the function
+    nested_bodies: usize,
 }
 
 impl BodyRewriter {
@@ -26,12 +37,35 @@ impl BodyRewriter {
         let _branched = branch_lock.try_lock();
         f(self)
     }
+
+    fn with_nested_body<F, O>(&mut self, f: F) -> O
+    where
+        F: FnOnce(&mut BodyRewriter) -> O,
+    {
+        self.nested_bodies += 1;
+        let output = self.with_branch(f);
+        self.nested_bodies -= 1;
+
+        output
+    }
+
+    fn record_early_exit(&mut self, early_exit: EarlyExit) {
+        if self.nested_bodies == 0 && self.early_exit.is_none() {
+            self.early_exit = Some(early_exit);
+        }
+    }
 }
 
 impl VisitMut for BodyRewriter {
     fn visit_expr_call_mut(&mut self, i: &mut ExprCall) {
         let ctx_ident = Ident::new("context", Span::mixed_site());
 
+        // The arguments are evaluated before the call, so a `?` or a `return` inside them
+        // can skip the hook too
+        for arg in i.args.iter_mut() {
+            self.visit_expr_mut(arg);
+        }
+
         // Only rewrite hook calls.
         if let Expr::Path(ref m) = &*i.func {
             if let Some(m) = m.path.segments.last().as_ref().map(|m| &m.ident) {
@@ -42,6 +76,17 @@ impl VisitMut for BodyRewriter {
                             "hooks cannot be called at this position.";
                             help = "move hooks to the top-level of your function.";
                         );
+                    } else if let Some(early_exit) = self.early_exit {
+                        let (span, exit) = match early_exit {
+                            EarlyExit::Return(span) => (span, "`return`"),
+                            EarlyExit::Try(span) => (span, "`?` operator"),
+                        };
+                        emit_error!(
+                            m,
+                            "hooks cannot be called after an early return.";
+                            help = "move hooks before the first `return` or `?` of your function.";
+                        );
+                        emit_error!(span, "this {} can skip the hook `{}`.", exit, m);
                     } else {
                         *i = parse_quote_spanned! { i.span() => ::review::HookBuilder::build(#i, #ctx_ident) };
                         //i.args.push(parse_quote!(#ctx_ident));
@@ -52,11 +97,27 @@ impl VisitMut for BodyRewriter {
             }
         }
 
-        visit_mut::visit_expr_call_mut(self, i);
+        self.visit_expr_mut(&mut i.func);
     }
 
     fn visit_expr_closure_mut(&mut self, i: &mut ExprClosure) {
-        self.with_branch(move |m| visit_mut::visit_expr_closure_mut(m, i))
+        self.with_nested_body(move |m| visit_mut::visit_expr_closure_mut(m, i))
+    }
+
+    fn visit_expr_async_mut(&mut self, i: &mut ExprAsync) {
+        self.with_nested_body(move |m| visit_mut::visit_expr_async_mut(m, i))
+    }
+
+    fn visit_expr_return_mut(&mut self, i: &mut ExprReturn) {
+        visit_mut::visit_expr_return_mut(self, i);
+
+        self.record_early_exit(EarlyExit::Return(i.return_token.span));
+    }
+
+    fn visit_expr_try_mut(&mut self, i: &mut ExprTry) {
+        visit_mut::visit_expr_try_mut(self, i);
+
+        self.record_early_exit(EarlyExit::Try(i.question_token.span));
     }
 
     fn visit_expr_if_mut(&mut self, i: &mut ExprIf) {
@@ -64,12 +125,12 @@ impl VisitMut for BodyRewriter {
             visit_mut::visit_attribute_mut(self, it);
         }
 
-        visit_mut::visit_expr_mut(self, &mut *i.cond);
+        visit_mut::visit_expr_mut(self, &mut i.cond);
 
         self.with_branch(|m| visit_mut::visit_block_mut(m, &mut i.then_branch));
 
         if let Some(it) = &mut i.else_branch {
-            self.with_branch(|m| visit_mut::visit_expr_mut(m, &mut *(it).1));
+            self.with_branch(|m| visit_mut::visit_expr_mut(m, &mut (it).1));
         }
     }
 
@@ -85,7 +146,7 @@ impl VisitMut for BodyRewriter {
             visit_mut::visit_label_mut(self, it);
         }
         visit_mut::visit_pat_mut(self, &mut i.pat);
-        visit_mut::visit_expr_mut(self, &mut *i.expr);
+        visit_mut::visit_expr_mut(self, &mut i.expr);
 
         self.with_branch(|m| visit_mut::visit_block_mut(m, &mut i.body));
     }
@@ -95,7 +156,7 @@ impl VisitMut for BodyRewriter {
             visit_mut::visit_attribute_mut(self, it);
         }
 
-        visit_mut::visit_expr_mut(self, &mut *i.expr);
+        visit_mut::visit_expr_mut(self, &mut i.expr);
 
         self.with_branch(|m| {
             for it in &mut i.arms {
@@ -1,16 +1,36 @@
 use crate::body::BodyRewriter;
+use crate::properties::properties_impl;
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::{visit_mut, Block, FnArg, Generics, Ident, Item, ItemFn, ReturnType, Type, Visibility};
+use syn::{
+    parse_quote, visit_mut, Attribute, Block, DeriveInput, FnArg, Generics, Ident, Item, ItemFn,
+    Pat, PatType, ReturnType, Type, Visibility,
+};
+
+use crate::html::to_pascal_case;
 
 extern crate self as review;
 
+/// How a function component receives its props
+enum ComponentProps {
+    /// A single reference to a props type, like `props: &BoardProps`
+    Reference { arg: FnArg, props_type: Box<Type> },
+    /// Several named arguments that become the fields of a generated props struct
+    Fields(Vec<PropsField>),
+}
+
+struct PropsField {
+    attrs: Vec<Attribute>,
+    mutability: Option<syn::token::Mut>,
+    name: Ident,
+    ty: Box<Type>,
+}
+
 pub(crate) struct Component {
     block: Box<Block>,
-    props_type: Box<Type>,
-    arg: FnArg,
+    props: ComponentProps,
     vis: Visibility,
     name: Ident,
     generics: Generics,
@@ -58,62 +78,48 @@ impl Parse for Component {
                     ReturnType::Type(_, ty) => ty,
                 };
 
-                let mut inputs = sig.inputs.into_iter();
-                let arg: FnArg = inputs
-                    .next()
-                    .unwrap_or_else(|| syn::parse_quote! { _: &() });
-
-                let ty = match &arg {
-                    FnArg::Typed(arg) => match &*arg.ty {
-                        Type::Reference(ty) => {
-                            if ty.lifetime.is_some() {
-                                return Err(syn::Error::new_spanned(
-                                    &ty.lifetime,
-                                    "reference must not have a lifetime",
-                                ));
-                            }
-
-                            if ty.mutability.is_some() {
-                                return Err(syn::Error::new_spanned(
-                                    &ty.mutability,
-                                    "reference must not be mutable",
-                                ));
-                            }
-
-                            ty.elem.clone()
-                        }
-                        ty => {
-                            let msg = format!(
-                                "expected a reference to a `Properties` type (try: `&{}`)",
-                                ty.to_token_stream()
-                            );
-                            return Err(syn::Error::new_spanned(ty, msg));
-                        }
-                    },
-
-                    FnArg::Receiver(_) => {
-                        return Err(syn::Error::new_spanned(
-                            arg,
-                            "function components can't accept a receiver",
-                        ));
+                let props = match sig.inputs.first() {
+                    Some(FnArg::Typed(PatType { ty, .. }))
+                        if sig.inputs.len() == 1 && matches!(**ty, Type::Reference(_)) =>
+                    {
+                        let arg = sig.inputs.into_iter().next().unwrap();
+                        let props_type = match &arg {
+                            FnArg::Typed(PatType { ty, .. }) => match &**ty {
+                                Type::Reference(ty) => {
+                                    if ty.lifetime.is_some() {
+                                        return Err(syn::Error::new_spanned(
+                                            &ty.lifetime,
+                                            "reference must not have a lifetime",
+                                        ));
+                                    }
+
+                                    if ty.mutability.is_some() {
+                                        return Err(syn::Error::new_spanned(
+                                            ty.mutability,
+                                            "reference must not be mutable",
+                                        ));
+                                    }
+
+                                    ty.elem.clone()
+                                }
+                                _ => unreachable!(),
+                            },
+                            FnArg::Receiver(_) => unreachable!(),
+                        };
+
+                        ComponentProps::Reference { arg, props_type }
                     }
+                    _ => ComponentProps::Fields(
+                        sig.inputs
+                            .into_iter()
+                            .map(props_field)
+                            .collect::<syn::Result<_>>()?,
+                    ),
                 };
 
-                // Checking after param parsing may make it a little inefficient
-                // but that's a requirement for better error messages in case of receivers
-                // `>0` because first one is already consumed.
-                if inputs.len() > 0 {
-                    let params: TokenStream = inputs.map(|it| it.to_token_stream()).collect();
-                    return Err(syn::Error::new_spanned(
-                        params,
-                        "function components can accept at most one parameter for the props",
-                    ));
-                }
-
                 Ok(Self {
-                    props_type: ty,
+                    props,
                     block,
-                    arg,
                     vis,
                     name: sig.ident,
                     generics: sig.generics,
@@ -128,19 +134,60 @@ impl Parse for Component {
     }
 }
 
+/// A named argument of a function component, that becomes a field of its props
+fn props_field(arg: FnArg) -> syn::Result<PropsField> {
+    match arg {
+        FnArg::Typed(PatType { attrs, pat, ty, .. }) => {
+            let (mutability, name) = match *pat {
+                Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                    (pat.mutability, pat.ident)
+                }
+                pat => {
+                    return Err(syn::Error::new_spanned(
+                        pat,
+                        "the arguments of function components must be named, like `value: u32`",
+                    ))
+                }
+            };
+
+            if let Type::Reference(_) = *ty {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "the arguments of function components must be owned, they are cloned from the props",
+                ));
+            }
+
+            Ok(PropsField {
+                attrs,
+                mutability,
+                name,
+                ty,
+            })
+        }
+        FnArg::Receiver(_) => Err(syn::Error::new_spanned(
+            arg,
+            "function components can't accept a receiver",
+        )),
+    }
+}
+
 pub(crate) struct ComponentName {
-    pub(crate) component_name: Ident,
+    pub(crate) component_name: Option<Ident>,
 }
 
 impl Parse for ComponentName {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         if input.is_empty() {
-            return Err(input.error("expected identifier for the component"));
+            return Ok(Self {
+                component_name: None,
+            });
         }
 
         let component_name = input.parse()?;
 
-        Ok(Self { component_name })
+        Ok(Self {
+            component_name: Some(component_name),
+        })
     }
 }
 
@@ -148,12 +195,9 @@ pub(crate) fn component_impl(
     name: ComponentName,
     component: Component,
 ) -> syn::Result<TokenStream> {
-    let ComponentName { component_name } = name;
-
     let Component {
         mut block,
-        props_type,
-        arg,
+        props,
         vis,
         name: function_name,
         generics,
@@ -161,6 +205,13 @@ pub(crate) fn component_impl(
         ..
     } = component;
 
+    let component_name = name.component_name.unwrap_or_else(|| {
+        Ident::new(
+            &to_pascal_case(&function_name.to_string()),
+            function_name.span(),
+        )
+    });
+
     if function_name == component_name {
         return Err(syn::Error::new_spanned(
             component_name,
@@ -174,11 +225,89 @@ pub(crate) fn component_impl(
     let ctx_ident = Ident::new("context", Span::mixed_site());
 
     let mut body_rewriter = BodyRewriter::default();
-    visit_mut::visit_block_mut(&mut body_rewriter, &mut *block);
+    visit_mut::visit_block_mut(&mut body_rewriter, &mut block);
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let (props_type, arg, props_struct, block) = match props {
+        ComponentProps::Reference { arg, props_type } => (
+            props_type.to_token_stream(),
+            arg.to_token_stream(),
+            None,
+            block.to_token_stream(),
+        ),
+        ComponentProps::Fields(fields) if fields.is_empty() => (
+            quote! { () },
+            quote! { _: &() },
+            None,
+            block.to_token_stream(),
+        ),
+        ComponentProps::Fields(fields) => {
+            let props_name = format_ident!("{}Props", component_name);
+            let props_ident = Ident::new("props", Span::mixed_site());
+
+            let names = fields.iter().map(|field| &field.name).collect::<Vec<_>>();
+            let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+            let field_attrs = fields
+                .iter()
+                .map(|field| {
+                    field
+                        .attrs
+                        .iter()
+                        .filter(|attr| !attr.path.is_ident("prop"))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let bindings = fields.iter().map(|PropsField { mutability, name, ty, .. }| {
+                quote_spanned! {ty.span()=>
+                    let #mutability #name: #ty = ::std::clone::Clone::clone(&#props_ident.#name);
+                }
+            });
+            let props_doc = format!("The props of the `{}` component", component_name);
+
+            let prop_attrs = fields.iter().map(|field| &field.attrs);
+            let props_input: DeriveInput = parse_quote! {
+                #[props(no_eq)]
+                #vis struct #props_name #generics #where_clause {
+                    #(#(#prop_attrs)* #names: #types,)*
+                }
+            };
+            let props_builder = properties_impl(&props_input)?;
+
+            let props_struct = quote! {
+                #[doc = #props_doc]
+                #vis struct #props_name #generics #where_clause {
+                    #(#(#field_attrs)* #vis #names: #types,)*
+                }
+
+                #props_builder
+
+                impl #impl_generics #component_name #ty_generics #where_clause {
+                    /// Creates the component with its props
+                    #[allow(clippy::too_many_arguments)]
+                    #vis fn new(#(#names: #types),*) -> Self {
+                        Self(#props_name { #(#names),* })
+                    }
+                }
+            };
+
+            (
+                quote! { #props_name #ty_generics },
+                quote! { #props_ident: &Self::Props },
+                Some(props_struct),
+                quote! {
+                    {
+                        #(#bindings)*
+                        #block
+                    }
+                },
+            )
+        }
+    };
+
     let quoted = quote! {
+        #props_struct
+
         #[doc(hidden)]
         #[allow(non_camel_case_types)]
         #vis struct #component_name #generics(pub #props_type) #where_clause;
@@ -200,6 +329,10 @@ pub(crate) fn component_impl(
             fn get_props<'a>(&'a self) -> &'a Self::Props {
                 &self.0
             }
+
+            fn get_mut_props<'a>(&'a mut self) -> &'a mut Self::Props {
+                &mut self.0
+            }
         }
     };
 
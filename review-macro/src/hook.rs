@@ -67,7 +67,7 @@ pub fn hook_impl(component: HookFn) -> syn::Result<TokenStream> {
     let ctx_ident = Ident::new("context", Span::mixed_site());
 
     let mut body_rewriter = BodyRewriter::default();
-    visit_mut::visit_block_mut(&mut body_rewriter, &mut *block);
+    visit_mut::visit_block_mut(&mut body_rewriter, &mut block);
 
     let prev_inputs = if !inputs.is_empty() {
         quote!( #inputs, )
@@ -0,0 +1,628 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{braced, token, Expr, Ident, Lit, Path, Token};
+
+/// The root of an `html!` invocation
+pub(crate) struct Html {
+    root: HtmlNode,
+}
+
+impl Parse for Html {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("expected a root node"));
+        }
+
+        let root = input.parse()?;
+
+        if !input.is_empty() {
+            return Err(
+                input.error("html! must have a single root node, wrap the nodes in an element")
+            );
+        }
+
+        Ok(Self { root })
+    }
+}
+
+enum HtmlNode {
+    Element(HtmlElement),
+    Component(HtmlComponent),
+    Text(Lit),
+    Expr(Expr),
+    Iter(Expr),
+}
+
+impl Parse for HtmlNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![<]) {
+            if input.peek2(Token![/]) {
+                return Err(input.error("unexpected closing tag"));
+            }
+
+            let tag: HtmlTag = input.parse()?;
+            if tag.is_component() {
+                HtmlComponent::from_tag(tag).map(HtmlNode::Component)
+            } else {
+                HtmlElement::from_tag(tag).map(HtmlNode::Element)
+            }
+        } else if input.peek(Lit) {
+            Ok(HtmlNode::Text(input.parse()?))
+        } else if input.peek(token::Brace) {
+            let content;
+            let braces = braced!(content in input);
+
+            if content.is_empty() {
+                return Err(syn::Error::new(
+                    braces.span,
+                    "expected an expression inside the braces",
+                ));
+            }
+
+            let node = if content.peek(Token![for]) {
+                content.parse::<Token![for]>()?;
+                HtmlNode::Iter(content.parse()?)
+            } else {
+                HtmlNode::Expr(content.parse()?)
+            };
+
+            if !content.is_empty() {
+                return Err(content.error("unexpected tokens after the expression"));
+            }
+
+            Ok(node)
+        } else {
+            Err(input.error(
+                "expected an element, a component, a string literal or an expression in braces",
+            ))
+        }
+    }
+}
+
+/// A tag with its attributes and its children, before it's known if it's an element or a component
+struct HtmlTag {
+    name: Path,
+    /// The whole name, that includes the dashes of custom elements like `my-widget`
+    name_string: String,
+    attributes: Vec<HtmlAttribute>,
+    spread: Option<Expr>,
+    children: Vec<HtmlNode>,
+}
+
+impl HtmlTag {
+    /// Components are named in PascalCase or with a path, elements are named in lowercase
+    /// or with dashes
+    fn is_component(&self) -> bool {
+        if self.name_string.contains('-') {
+            return false;
+        }
+
+        self.name.segments.len() > 1
+            || self
+                .name
+                .segments
+                .last()
+                .and_then(|segment| segment.ident.to_string().chars().next())
+                .map_or(false, char::is_uppercase)
+    }
+}
+
+impl Parse for HtmlTag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![<]>()?;
+        let (name, name_tokens, tag_name) = parse_tag_name(input)?;
+
+        let mut attributes: Vec<HtmlAttribute> = Vec::new();
+        let mut spread = None;
+        while !(input.peek(Token![/]) || input.peek(Token![>])) {
+            if input.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    &name_tokens,
+                    format!("expected `>` or `/>` to end the tag `<{}>`", tag_name),
+                ));
+            }
+
+            if input.peek(Token![..]) {
+                let dots = input.parse::<Token![..]>()?;
+                if spread.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        dots,
+                        "props can be spread only once",
+                    ));
+                }
+                spread = Some(parse_spread(input)?);
+                continue;
+            }
+
+            if spread.is_some() {
+                return Err(input.error("the spread props must be the last attribute"));
+            }
+
+            let attribute: HtmlAttribute = input.parse()?;
+            if attributes
+                .iter()
+                .any(|other| other.name_string == attribute.name_string)
+            {
+                return Err(syn::Error::new_spanned(
+                    &attribute.name,
+                    format!("the attribute `{}` is set twice", attribute.name_string),
+                ));
+            }
+            attributes.push(attribute);
+        }
+
+        let mut children = Vec::new();
+        if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+        } else {
+            input.parse::<Token![>]>()?;
+
+            while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+                if input.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        &name_tokens,
+                        format!("the tag `<{}>` is never closed", tag_name),
+                    ));
+                }
+
+                children.push(input.parse()?);
+            }
+
+            input.parse::<Token![<]>()?;
+            input.parse::<Token![/]>()?;
+            let (_, closing_tokens, closing_name) = parse_tag_name(input)?;
+            if closing_name != tag_name {
+                return Err(syn::Error::new_spanned(
+                    closing_tokens,
+                    format!("expected the closing tag `</{}>`", tag_name),
+                ));
+            }
+            input.parse::<Token![>]>()?;
+        }
+
+        Ok(Self {
+            name,
+            name_string: tag_name,
+            attributes,
+            spread,
+            children,
+        })
+    }
+}
+
+/// Parses the name of a tag, returning its path, its tokens and its string.
+/// The name of an element can contain dashes, like `sl-button`
+fn parse_tag_name(input: ParseStream) -> syn::Result<(Path, TokenStream, String)> {
+    let name = input.call(Path::parse_mod_style)?;
+    let mut name_tokens = name.to_token_stream();
+    let mut name_string = path_to_string(&name);
+
+    if name.get_ident().is_some() {
+        while input.peek(Token![-]) {
+            let dash = input.parse::<Token![-]>()?;
+            let part = input.call(Ident::parse_any)?;
+            name_string.push('-');
+            name_string.push_str(&part.unraw().to_string());
+            dash.to_tokens(&mut name_tokens);
+            part.to_tokens(&mut name_tokens);
+        }
+    }
+
+    Ok((name, name_tokens, name_string))
+}
+
+fn parse_spread(input: ParseStream) -> syn::Result<Expr> {
+    if input.peek(token::Brace) {
+        let content;
+        braced!(content in input);
+        content.parse()
+    } else {
+        input.call(Path::parse_mod_style).map(|path| {
+            Expr::Path(syn::ExprPath {
+                attrs: Vec::new(),
+                qself: None,
+                path,
+            })
+        })
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_token_stream().to_string().replace(' ', "")
+}
+
+struct HtmlAttribute {
+    /// The tokens of the name, used to point errors at it
+    name: TokenStream,
+    name_string: String,
+    name_span: Span,
+    value: Option<AttributeValue>,
+}
+
+impl Parse for HtmlAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Attribute names can be keywords, like `type` or `for`, and contain dashes, like `data-id`
+        let first = input.call(Ident::parse_any)?;
+        let name_span = first.span();
+        let mut name_string = first.unraw().to_string();
+        let mut name = first.to_token_stream();
+        while input.peek(Token![-]) {
+            let dash = input.parse::<Token![-]>()?;
+            let part = input.call(Ident::parse_any)?;
+            name_string.push('-');
+            name_string.push_str(&part.unraw().to_string());
+            dash.to_tokens(&mut name);
+            part.to_tokens(&mut name);
+        }
+
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name,
+            name_string,
+            name_span,
+            value,
+        })
+    }
+}
+
+enum AttributeValue {
+    Lit(Lit),
+    Expr(Box<Expr>),
+}
+
+impl Parse for AttributeValue {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Lit) {
+            Ok(AttributeValue::Lit(input.parse()?))
+        } else if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            Ok(AttributeValue::Expr(Box::new(content.parse()?)))
+        } else {
+            Err(input.error("expected a literal or an expression in braces"))
+        }
+    }
+}
+
+impl ToTokens for AttributeValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            AttributeValue::Lit(lit) => lit.to_tokens(tokens),
+            AttributeValue::Expr(expr) => expr.to_tokens(tokens),
+        }
+    }
+}
+
+struct HtmlElement {
+    tag: Ident,
+    /// The name of a custom element, that contains dashes
+    custom_name: Option<String>,
+    attributes: Vec<HtmlAttribute>,
+    children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+    fn from_tag(tag: HtmlTag) -> syn::Result<Self> {
+        let HtmlTag {
+            name,
+            name_string,
+            attributes,
+            spread,
+            children,
+            ..
+        } = tag;
+
+        if let Some(spread) = spread {
+            return Err(syn::Error::new_spanned(
+                spread,
+                "only components accept spread props",
+            ));
+        }
+
+        let custom_name = Some(name_string).filter(|name| name.contains('-'));
+
+        let tag = name.get_ident().cloned().ok_or_else(|| {
+            syn::Error::new_spanned(&name, "expected the name of an element, like `div`")
+        })?;
+
+        Ok(Self {
+            tag,
+            custom_name,
+            attributes,
+            children,
+        })
+    }
+}
+
+struct HtmlComponent {
+    name: Path,
+    props: Vec<(Ident, AttributeValue)>,
+    spread: Option<Expr>,
+    children: Vec<HtmlNode>,
+}
+
+impl HtmlComponent {
+    fn from_tag(tag: HtmlTag) -> syn::Result<Self> {
+        let HtmlTag {
+            name,
+            attributes,
+            spread,
+            children,
+            ..
+        } = tag;
+
+        if !children.is_empty() {
+            if let Some(attribute) = attributes
+                .iter()
+                .find(|attribute| attribute.name_string == "children")
+            {
+                return Err(syn::Error::new_spanned(
+                    &attribute.name,
+                    "the children are set twice, as a prop and inside the component",
+                ));
+            }
+        }
+
+        let props = attributes
+            .into_iter()
+            .map(|attribute| {
+                if attribute.name_string.contains('-') {
+                    return Err(syn::Error::new_spanned(
+                        &attribute.name,
+                        "the props of a component must be valid field names",
+                    ));
+                }
+
+                let field = Ident::new(&attribute.name_string, attribute.name_span);
+                match attribute.value {
+                    Some(value) => Ok((field, value)),
+                    None => Err(syn::Error::new_spanned(
+                        &attribute.name,
+                        format!("expected a value for the prop `{}`", attribute.name_string),
+                    )),
+                }
+            })
+            .collect::<syn::Result<_>>()?;
+
+        Ok(Self {
+            name,
+            props,
+            spread,
+            children,
+        })
+    }
+}
+
+impl HtmlNode {
+    /// An expression evaluating to a `VNode`
+    fn to_vnode(&self) -> syn::Result<TokenStream> {
+        match self {
+            HtmlNode::Element(element) => element.to_vnode(),
+            HtmlNode::Component(component) => component.to_vnode(),
+            HtmlNode::Text(Lit::Str(text)) => Ok(quote! { ::review::VNode::from(#text) }),
+            HtmlNode::Text(lit) => {
+                Ok(quote! { ::review::VNode::from(::std::string::ToString::to_string(&#lit)) })
+            }
+            HtmlNode::Expr(expr) => Ok(quote_spanned! {expr.span()=>
+                ::std::convert::Into::<::review::VNode>::into(#expr)
+            }),
+            HtmlNode::Iter(expr) => Err(syn::Error::new_spanned(
+                expr,
+                "an iterator of nodes can only be a child of an element",
+            )),
+        }
+    }
+}
+
+impl HtmlElement {
+    fn to_vnode(&self) -> syn::Result<TokenStream> {
+        let element = Ident::new("element", Span::mixed_site());
+
+        let tag = match &self.custom_name {
+            Some(name) => quote_spanned! {self.tag.span()=>
+                ::review::Tag::Custom(::std::borrow::Cow::Borrowed(#name))
+            },
+            None => {
+                let tag_name = self.tag.unraw().to_string();
+                let variant = Ident::new(&to_pascal_case(&tag_name), self.tag.span());
+                quote_spanned! {self.tag.span()=> ::review::Tag::#variant }
+            }
+        };
+
+        let children = children_vec(&self.children)?;
+
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|attribute| attribute_setter(&element, attribute))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            {
+                let #element = ::review::ElementBuilder::with_children(#tag, #children);
+                #(let #element = #attributes;)*
+                ::review::VNode::from(#element)
+            }
+        })
+    }
+}
+
+/// An expression evaluating to the `Vec<VNode>` of the children
+fn children_vec(children: &[HtmlNode]) -> syn::Result<TokenStream> {
+    if !children
+        .iter()
+        .any(|child| matches!(child, HtmlNode::Iter(_)))
+    {
+        let children = children
+            .iter()
+            .map(HtmlNode::to_vnode)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        return Ok(quote! { ::std::vec![#(#children),*] });
+    }
+
+    let vec = Ident::new("children", Span::mixed_site());
+    let mut parts = Vec::new();
+    let mut nodes = Vec::new();
+    for child in children {
+        if let HtmlNode::Iter(expr) = child {
+            if !nodes.is_empty() {
+                parts.push(quote! { [#(#nodes),*] });
+                nodes.clear();
+            }
+            parts.push(quote_spanned! {expr.span()=>
+                ::std::iter::Iterator::map(
+                    ::std::iter::IntoIterator::into_iter(#expr),
+                    ::std::convert::Into::<::review::VNode>::into,
+                )
+            });
+        } else {
+            nodes.push(child.to_vnode()?);
+        }
+    }
+    if !nodes.is_empty() {
+        parts.push(quote! { [#(#nodes),*] });
+    }
+
+    Ok(quote! {
+        {
+            let mut #vec = ::std::vec::Vec::<::review::VNode>::new();
+            #(::std::iter::Extend::extend(&mut #vec, #parts);)*
+            #vec
+        }
+    })
+}
+
+/// An expression that sets the attribute, or the event, on `element`
+fn attribute_setter(element: &Ident, attribute: &HtmlAttribute) -> syn::Result<TokenStream> {
+    let HtmlAttribute {
+        name,
+        name_string,
+        name_span,
+        value,
+    } = attribute;
+
+    if let Some(event_name) = name_string.strip_prefix("on_") {
+        let variant = Ident::new(&format!("On{}", to_pascal_case(event_name)), *name_span);
+        let event_type = quote_spanned! {*name_span=> ::review::EventType::#variant };
+
+        return match value {
+            Some(AttributeValue::Expr(callback)) => Ok(quote_spanned! {callback.span()=>
+                ::review::ElementBuilder::with_event(#element, #event_type, #callback)
+            }),
+            _ => Err(syn::Error::new_spanned(
+                name,
+                format!(
+                    "expected a callback for the event `{}`, like `{}={{callback!(|| ...)}}`",
+                    name_string, name_string
+                ),
+            )),
+        };
+    }
+
+    let value = match value {
+        None => quote! { "" },
+        Some(AttributeValue::Lit(Lit::Str(value))) => value.to_token_stream(),
+        Some(AttributeValue::Lit(value)) => {
+            let value = value.to_token_stream().to_string();
+            quote! { #value }
+        }
+        Some(AttributeValue::Expr(value)) => quote_spanned! {value.span()=>
+            &::std::string::ToString::to_string(&#value)
+        },
+    };
+
+    Ok(quote! {
+        ::review::ElementBuilder::with_attribute(#element, #name_string, #value)
+    })
+}
+
+impl HtmlComponent {
+    fn to_vnode(&self) -> syn::Result<TokenStream> {
+        let HtmlComponent {
+            name,
+            props,
+            spread,
+            children,
+        } = self;
+
+        let mut children = if children.is_empty() {
+            None
+        } else {
+            Some(children_vec(children)?)
+        };
+
+        let props = match (props.is_empty(), spread) {
+            (true, Some(spread)) => quote! { #spread },
+            (false, Some(spread)) => {
+                let props_type = Ident::new("Props", Span::mixed_site());
+                let fields = props.iter().map(|(field, value)| quote! { #field: #value });
+
+                quote! {
+                    {
+                        type #props_type = <#name as ::review::ComponentProvider>::Props;
+                        #props_type {
+                            #(#fields,)*
+                            ..#spread
+                        }
+                    }
+                }
+            }
+            (_, None) => {
+                let setters = props
+                    .iter()
+                    .map(|(field, value)| quote_spanned! {field.span()=> .#field(#value) });
+                let children_setter = children.take().map(|children| {
+                    let field = Ident::new("children", name.span());
+                    quote! { .#field(::review::Children::from(#children)) }
+                });
+                let build = quote_spanned! {name.span()=> .build() };
+
+                quote! {
+                    <<#name as ::review::ComponentProvider>::Props as ::review::Properties>::builder()
+                        #(#setters)*
+                        #children_setter
+                        #build
+                }
+            }
+        };
+
+        let component = quote! { #name(#props) };
+        let component = match children {
+            Some(children) => quote_spanned! {name.span()=>
+                ::review::ComponentBuilder::with_children(#component, #children)
+            },
+            None => component,
+        };
+
+        Ok(quote! { ::review::VNode::from(#component) })
+    }
+}
+
+pub(crate) fn html_impl(html: Html) -> syn::Result<TokenStream> {
+    html.root.to_vnode()
+}
+
+/// Converts `snake_case` and `lowercase` names to `PascalCase`
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
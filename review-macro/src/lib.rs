@@ -1,11 +1,16 @@
 use crate::component::{component_impl, Component, ComponentName};
 use crate::hook::{hook_impl, HookFn};
-use syn::parse_macro_input;
+use crate::html::{html_impl, Html};
+use crate::properties::properties_impl;
+use syn::{parse_macro_input, DeriveInput};
 
 mod body;
 mod component;
 mod hook;
+mod html;
+mod properties;
 
+#[proc_macro_error::proc_macro_error]
 #[proc_macro_attribute]
 pub fn component(
     attr: proc_macro::TokenStream,
@@ -37,3 +42,21 @@ pub fn hook(
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+#[proc_macro]
+pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let html = parse_macro_input!(input as Html);
+
+    html_impl(html)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(Properties, attributes(prop, props))]
+pub fn properties(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    properties_impl(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
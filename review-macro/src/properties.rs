@@ -0,0 +1,462 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, PathArguments, Token, Type,
+};
+
+/// How a field of the props is filled when the builder doesn't set it
+enum PropKind {
+    /// The builder can't be built until the field is set
+    Required,
+    /// The field falls back to `Default::default()` or to the given expression
+    Default(Option<Box<Expr>>),
+    /// An `Option` field that falls back to `None`, its setter takes the inner value
+    Optional(Box<Type>),
+}
+
+struct PropField {
+    name: Ident,
+    ty: Type,
+    kind: PropKind,
+    into: bool,
+}
+
+/// An option of a `#[prop(...)]` attribute
+enum PropOption {
+    Default(Ident, Option<Box<Expr>>),
+    Into,
+    Optional(Ident),
+}
+
+impl Parse for PropOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+
+        match name.to_string().as_str() {
+            "default" if input.peek(Token![=]) => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Default(name, Some(input.parse()?)))
+            }
+            "default" => Ok(Self::Default(name, None)),
+            "into" => Ok(Self::Into),
+            "optional" => Ok(Self::Optional(name)),
+            _ => Err(syn::Error::new_spanned(
+                name,
+                "unknown prop option, expected `default`, `into` or `optional`",
+            )),
+        }
+    }
+}
+
+impl PropField {
+    fn new(name: Ident, ty: Type, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut kind = PropKind::Required;
+        let mut into = false;
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("prop")) {
+            let options =
+                attr.parse_args_with(Punctuated::<PropOption, Token![,]>::parse_terminated)?;
+
+            for option in options {
+                match option {
+                    PropOption::Into => into = true,
+                    PropOption::Default(option, _) | PropOption::Optional(option)
+                        if !matches!(kind, PropKind::Required) =>
+                    {
+                        return Err(syn::Error::new_spanned(
+                            option,
+                            "a prop can be either `default` or `optional`",
+                        ))
+                    }
+                    PropOption::Default(_, value) => kind = PropKind::Default(value),
+                    PropOption::Optional(option) => match option_inner(&ty) {
+                        Some(inner) => kind = PropKind::Optional(Box::new(inner.clone())),
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                option,
+                                "only `Option` fields can be `optional`",
+                            ))
+                        }
+                    },
+                }
+            }
+        }
+
+        Ok(Self {
+            name,
+            ty,
+            kind,
+            into,
+        })
+    }
+
+    /// The type accepted by the setter of the field
+    fn setter_type(&self) -> TokenStream {
+        let ty = match &self.kind {
+            PropKind::Optional(inner) => &**inner,
+            _ => &self.ty,
+        };
+
+        if self.into {
+            quote_spanned! {ty.span()=> impl ::std::convert::Into<#ty> }
+        } else {
+            quote! { #ty }
+        }
+    }
+
+    /// The value stored by the setter of the field
+    fn setter_value(&self) -> TokenStream {
+        let name = &self.name;
+
+        if self.into {
+            quote! { ::std::convert::Into::into(#name) }
+        } else {
+            quote! { #name }
+        }
+    }
+}
+
+/// The inner type of an `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args)
+            if segment.ident == "Option" && args.args.len() == 1 =>
+        {
+            match args.args.first()? {
+                GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the type is `Children`, with or without a path
+fn is_children(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last().map_or(false, |segment| {
+            segment.ident == "Children" && segment.arguments.is_empty()
+        }),
+        _ => false,
+    }
+}
+
+/// An option of a `#[props(...)]` attribute on the struct
+enum PropsOption {
+    NoEq,
+}
+
+impl Parse for PropsOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+
+        match name.to_string().as_str() {
+            "no_eq" => Ok(Self::NoEq),
+            _ => Err(syn::Error::new_spanned(
+                name,
+                "unknown props option, expected `no_eq`",
+            )),
+        }
+    }
+}
+
+/// Generates the builder of the props
+pub(crate) fn properties_impl(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(|field| {
+                    PropField::new(field.ident.clone().unwrap(), field.ty.clone(), &field.attrs)
+                })
+                .collect::<syn::Result<Vec<_>>>()?,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "only structs with named fields can be props",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "only structs with named fields can be props",
+            ))
+        }
+    };
+
+    let mut derive_eq = true;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("props"))
+    {
+        let options =
+            attr.parse_args_with(Punctuated::<PropsOption, Token![,]>::parse_terminated)?;
+
+        for option in options {
+            match option {
+                PropsOption::NoEq => derive_eq = false,
+            }
+        }
+    }
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let builder = format_ident!("{}Builder", name);
+    let builder_doc = format!("The builder of [`{}`]", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let generic_params = input.generics.params.iter().collect::<Vec<_>>();
+    let generic_args = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Every required field is tracked by a type parameter of the builder, that is the missing
+    // marker of the field until it's set, so `build` is only available once every one is set.
+    // The markers live in a hidden module, so they don't pollute the module of the props
+    let markers_mod = format_ident!("__{}Markers", name);
+    let required = fields
+        .iter()
+        .filter(|field| matches!(field.kind, PropKind::Required))
+        .collect::<Vec<_>>();
+    let slots = (0..required.len())
+        .map(|index| format_ident!("__Prop{}", index))
+        .collect::<Vec<_>>();
+    let field_tags = required
+        .iter()
+        .map(|field| field.name.clone())
+        .collect::<Vec<_>>();
+    let markers = field_tags
+        .iter()
+        .map(|tag| quote_spanned! {tag.span()=> #markers_mod::Missing<#markers_mod::#tag> })
+        .collect::<Vec<_>>();
+
+    let builder_fields = fields.iter().map(|field| {
+        let field_name = &field.name;
+        let ty = &field.ty;
+
+        match required
+            .iter()
+            .position(|required| required.name == field.name)
+        {
+            Some(index) => {
+                let slot = &slots[index];
+                quote! { #field_name: #slot }
+            }
+            None => quote! { #field_name: ::std::option::Option<#ty> },
+        }
+    });
+
+    let initial_fields = fields.iter().map(|field| {
+        let field_name = &field.name;
+
+        match required
+            .iter()
+            .position(|required| required.name == field.name)
+        {
+            Some(_) => quote! {
+                #field_name: #markers_mod::Missing(::std::marker::PhantomData)
+            },
+            None => quote! { #field_name: ::std::option::Option::None },
+        }
+    });
+
+    let required_setters = required.iter().enumerate().map(|(index, field)| {
+        let field_name = &field.name;
+        let ty = &field.ty;
+        let setter_type = field.setter_type();
+        let setter_value = field.setter_value();
+
+        let other_slots = slots
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(_, slot)| slot);
+        let unset_slots = slots.iter().enumerate().map(|(other, slot)| {
+            if other == index {
+                let marker = &markers[index];
+                quote! { #marker }
+            } else {
+                quote! { #slot }
+            }
+        });
+        let set_slots = slots.iter().enumerate().map(|(other, slot)| {
+            if other == index {
+                quote! { ::review::PropValue<#ty> }
+            } else {
+                quote! { #slot }
+            }
+        });
+        let moved_fields = fields
+            .iter()
+            .filter(|other| other.name != field.name)
+            .map(|other| &other.name);
+
+        quote! {
+            impl<#(#generic_params,)* #(#other_slots),*> #builder<#(#generic_args,)* #(#unset_slots),*> #where_clause {
+                #vis fn #field_name(self, #field_name: #setter_type) -> #builder<#(#generic_args,)* #(#set_slots),*> {
+                    #builder {
+                        #field_name: ::review::PropValue(#setter_value),
+                        #(#moved_fields: self.#moved_fields,)*
+                        __props: ::std::marker::PhantomData,
+                    }
+                }
+            }
+        }
+    });
+
+    let optional_setters = fields
+        .iter()
+        .filter(|field| !matches!(field.kind, PropKind::Required))
+        .map(|field| {
+            let field_name = &field.name;
+            let setter_type = field.setter_type();
+            let setter_value = field.setter_value();
+
+            let value = match field.kind {
+                PropKind::Optional(_) => quote! { ::std::option::Option::Some(#setter_value) },
+                _ => setter_value,
+            };
+
+            quote! {
+                #vis fn #field_name(mut self, #field_name: #setter_type) -> Self {
+                    self.#field_name = ::std::option::Option::Some(#value);
+                    self
+                }
+            }
+        });
+
+    let built_fields = fields.iter().map(|field| {
+        let field_name = &field.name;
+
+        match &field.kind {
+            PropKind::Required => {
+                quote! { #field_name: ::review::RequiredProp::value(self.#field_name) }
+            }
+            PropKind::Default(None) => quote! {
+                #field_name: ::std::option::Option::unwrap_or_default(self.#field_name)
+            },
+            PropKind::Default(Some(value)) => quote! {
+                #field_name: ::std::option::Option::unwrap_or_else(self.#field_name, || #value)
+            },
+            PropKind::Optional(_) => quote! {
+                #field_name: ::std::option::Option::flatten(self.#field_name)
+            },
+        }
+    });
+
+    let required_bounds = required.iter().zip(&slots).map(|(field, slot)| {
+        let ty = &field.ty;
+        quote! { #slot: ::review::RequiredProp<#ty> }
+    });
+
+    // A `children: Children` field can be filled by `ComponentBuilder::with_children`
+    let children_props = fields
+        .iter()
+        .find(|field| field.name == "children" && is_children(&field.ty))
+        .map(|field| {
+            let field_name = &field.name;
+
+            quote! {
+                impl #impl_generics ::review::ChildrenProps for #name #ty_generics #where_clause {
+                    fn children_mut(&mut self) -> &mut ::review::Children {
+                        &mut self.#field_name
+                    }
+                }
+            }
+        });
+
+    // Compares every field, every type parameter has to be comparable
+    let partial_eq = derive_eq.then(|| {
+        let names = fields.iter().map(|field| &field.name);
+        let mut generics = input.generics.clone();
+        for param in generics.type_params_mut() {
+            param
+                .bounds
+                .push(syn::parse_quote! { ::std::cmp::PartialEq });
+        }
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::std::cmp::PartialEq for #name #ty_generics #where_clause {
+                fn eq(&self, other: &Self) -> bool {
+                    true #(&& self.#names == other.#names)*
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types, non_snake_case)]
+        mod #markers_mod {
+            /// A required field that isn't set yet, `F` is named after the field
+            pub struct Missing<F>(pub ::std::marker::PhantomData<F>);
+
+            #(pub enum #field_tags {})*
+        }
+
+        #[doc = #builder_doc]
+        #vis struct #builder<#(#generic_params,)* #(#slots),*> #where_clause {
+            #(#builder_fields,)*
+            __props: ::std::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #impl_generics ::review::Properties for #name #ty_generics #where_clause {
+            type Builder = #builder<#(#generic_args,)* #(#markers),*>;
+
+            fn builder() -> Self::Builder {
+                #builder {
+                    #(#initial_fields,)*
+                    __props: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        #(#required_setters)*
+
+        impl<#(#generic_params,)* #(#slots),*> #builder<#(#generic_args,)* #(#slots),*> #where_clause {
+            #(#optional_setters)*
+
+            #vis fn build(self) -> #name #ty_generics
+            where
+                #(#required_bounds,)*
+            {
+                #name {
+                    #(#built_fields,)*
+                }
+            }
+        }
+
+        #children_props
+
+        #partial_eq
+    })
+}
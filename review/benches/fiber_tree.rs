@@ -0,0 +1,68 @@
+//! Times the fiber tree on a tree of 10,000 fibers, run with `cargo bench --bench fiber_tree`
+
+use review::BenchTree;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 10_000;
+const RUNS: usize = 20;
+
+/// The fastest of a few runs of `measure`, that sets up its own tree and times a part of the work
+fn bench(name: &str, measure: impl Fn() -> Duration) {
+    let fastest = (0..RUNS).map(|_| measure()).min().unwrap_or_default();
+    println!("{:<40} {:?}", name, fastest);
+}
+
+fn main() {
+    bench("create a tree", || {
+        let mut tree = BenchTree::default();
+        let start = Instant::now();
+        tree.create_tree(SIZE);
+        start.elapsed()
+    });
+
+    bench("remove a tree", || {
+        let mut tree = BenchTree::default();
+        let root_id = tree.create_tree(SIZE);
+        let start = Instant::now();
+        tree.remove(root_id);
+        start.elapsed()
+    });
+
+    bench("create a tree in the free slots", || {
+        let mut tree = BenchTree::default();
+        let root_id = tree.create_tree(SIZE);
+        tree.remove(root_id);
+        let start = Instant::now();
+        tree.create_tree(SIZE);
+        start.elapsed()
+    });
+
+    bench("remove the children from the last", || {
+        let mut tree = BenchTree::default();
+        let (_, children) = tree.create_children(SIZE);
+        let start = Instant::now();
+        for id in children {
+            tree.remove(id);
+        }
+        start.elapsed()
+    });
+
+    bench("remove the children from the first", || {
+        let mut tree = BenchTree::default();
+        let (_, children) = tree.create_children(SIZE);
+        let start = Instant::now();
+        for id in children.into_iter().rev() {
+            tree.remove(id);
+        }
+        start.elapsed()
+    });
+
+    bench("compact a tree", || {
+        let mut tree = BenchTree::default();
+        let root_id = tree.create_tree(SIZE);
+        tree.remove(root_id);
+        let start = Instant::now();
+        tree.compact();
+        start.elapsed()
+    });
+}
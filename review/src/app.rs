@@ -1,10 +1,12 @@
 use crate::fiber::{FiberId, FiberTree};
+use crate::hooks::clear_pass_snapshots;
 use crate::node::{Element, Node};
-use crate::reconciliation::{commit, perform_unit_of_work};
+use crate::reconciliation::{commit, perform_unit_of_work, run_component_effects};
 use crate::request_animation_frame;
-use crate::{Events, Tag, VNode};
+use crate::scheduler::start_next_update;
+use crate::{Events, HookContext, Tag, VNode};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 
@@ -13,6 +15,11 @@ pub(crate) struct App {
     pub fiber_tree: FiberTree,
     pub wip_root: Option<FiberId>,
     pub next_unit_of_work: Option<FiberId>,
+    /// The fibers changed by the work in progress, applied by the next commit
+    pub effects: Vec<FiberId>,
+    /// The components rendered by the work in progress, in the order they complete,
+    /// so the commit runs their effects without walking the whole tree
+    pub rendered: Vec<FiberId>,
     pub document: Option<web_sys::Document>,
 }
 
@@ -48,6 +55,8 @@ pub fn render(element: VNode, container_id: &str) {
             fiber_tree,
             next_unit_of_work: Some(root_id),
             wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
             document: Some(web_sys::window().unwrap().document().unwrap()),
         }));
 
@@ -62,6 +71,7 @@ pub fn render(element: VNode, container_id: &str) {
                     if let Some(app) = &mut *app {
                         let deadline = instant::Instant::now();
                         let check_deadline = || deadline.elapsed() < run_for;
+                        start_next_update(app);
                         work_loop(app, check_deadline);
 
                         commit_work(app, check_deadline);
@@ -75,26 +85,525 @@ pub fn render(element: VNode, container_id: &str) {
     });
 }
 
+/// Releases the memory of the free fiber slots at the end of the tree of the app.
+///
+/// The slots of the removed fibers are reused by the next ones, so the tree never grows past its
+/// largest size. Call it after a large part of the page is removed for good, to give that memory back.
+/// It walks every slot of the tree, so it isn't meant to be called at every update, and it does
+/// nothing when called while the app is rendering.
+pub fn compact_fibers() {
+    APP.with(|app| {
+        if let Ok(mut app) = app.try_borrow_mut() {
+            if let Some(app) = &mut *app {
+                app.fiber_tree.compact();
+            }
+        }
+    });
+}
+
 pub(crate) fn work_loop<F: Fn() -> bool>(app: &mut App, continue_working: F) {
     while app.next_unit_of_work.is_some() && continue_working() {
         if let Some(current_id) = app.next_unit_of_work {
-            app.next_unit_of_work =
-                perform_unit_of_work(current_id, &mut app.fiber_tree, app.document.as_ref());
+            app.next_unit_of_work = perform_unit_of_work(
+                current_id,
+                &mut app.fiber_tree,
+                app.document.as_ref(),
+                &mut app.wip_root,
+                &mut app.effects,
+                &mut app.rendered,
+            );
         }
     }
 }
 
 pub(crate) fn commit_work<F: Fn() -> bool>(app: &mut App, continue_working: F) {
-    if let Some(wip_root) = app.wip_root {
-        if continue_working() {
-            if let Some(child_id) = app
-                .fiber_tree
-                .get(wip_root)
-                .and_then(|wip_node| wip_node.child)
+    if app.wip_root.is_some() {
+        // A render is committed only once it's complete
+        if app.next_unit_of_work.is_none() && continue_working() {
+            render_torn_components(app);
+            clear_pass_snapshots();
+
+            let effects = std::mem::take(&mut app.effects);
+            commit(&effects, &mut app.fiber_tree);
+
+            let rendered = completed_once(std::mem::take(&mut app.rendered));
+            run_component_effects(
+                &rendered,
+                &mut app.fiber_tree,
+                HookContext::run_layout_effects,
+            );
+            run_component_effects(&rendered, &mut app.fiber_tree, HookContext::run_effects);
+
+            app.wip_root = None;
+        }
+    }
+}
+
+/// How many times a commit renders again the components that read an outdated value
+const MAX_TORN_RENDERS: usize = 10;
+
+/// Renders again, before anything is committed, the components that read a value that changed
+/// while the tree was rendered, like the snapshot of an external store.
+///
+/// Nothing else runs until the commit, so the committed tree never shows two snapshots of the same source.
+fn render_torn_components(app: &mut App) {
+    for _ in 0..MAX_TORN_RENDERS {
+        let torn: Vec<FiberId> = app
+            .rendered
+            .iter()
+            .copied()
+            .filter(|id| is_torn(&app.fiber_tree, *id))
+            .collect();
+        if torn.is_empty() {
+            return;
+        }
+
+        clear_pass_snapshots();
+        // The parents come first, so the children they render again are no longer torn
+        for id in torn.into_iter().rev() {
+            if !is_torn(&app.fiber_tree, id) {
+                continue;
+            }
+
+            let wip_root = app.wip_root;
+            app.wip_root = Some(id);
+            app.next_unit_of_work = Some(id);
+            work_loop(app, || true);
+
+            // A suspense boundary outside of the work in progress becomes its root
+            app.wip_root = match (wip_root, app.wip_root) {
+                (Some(root_id), Some(new_root_id))
+                    if app.fiber_tree.is_ancestor(new_root_id, root_id) =>
+                {
+                    Some(new_root_id)
+                }
+                _ => wip_root,
+            };
+        }
+    }
+
+    log::error!(
+        "the external sources kept changing while rendering, committing after {} renders",
+        MAX_TORN_RENDERS
+    );
+}
+
+fn is_torn(fiber_tree: &FiberTree, id: FiberId) -> bool {
+    matches!(
+        fiber_tree.get(id).map(|fiber_node| &fiber_node.node),
+        Some(Node::Component(component)) if component.hook_context.is_torn()
+    )
+}
+
+/// Keeps the first completion of the components rendered again in the same pass,
+/// which is still before the completion of their parents
+fn completed_once(rendered: Vec<FiberId>) -> Vec<FiberId> {
+    let mut seen = HashSet::with_capacity(rendered.len());
+
+    rendered.into_iter().filter(|id| seen.insert(*id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as review;
+    use crate::fiber::FiberNode;
+    use crate::node::Text;
+    use crate::scheduler::take_scheduled_updates;
+    use crate::Tag::Div;
+    use crate::{
+        component, html, use_effect, use_external_store, use_layout_effect, use_state, Children,
+        ElementBuilder, Link, ShouldRender, StructComponent, StructNode, UseStateHandle,
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        static LEFT: RefCell<Option<UseStateHandle<u32>>> = RefCell::new(None);
+        static RIGHT: RefCell<Option<UseStateHandle<u32>>> = RefCell::new(None);
+        static FRAMED: RefCell<Option<UseStateHandle<u32>>> = RefCell::new(None);
+        static TOGGLE: RefCell<Option<Link<Toggle>>> = RefCell::new(None);
+        static ROWS: RefCell<Option<UseStateHandle<u32>>> = RefCell::new(None);
+        static ROWS_EFFECTS: Cell<u32> = Cell::new(0);
+        static SOURCE: Cell<u32> = Cell::new(0);
+        static READERS: RefCell<Option<UseStateHandle<u32>>> = RefCell::new(None);
+        static PAINTED: RefCell<Vec<u32>> = RefCell::new(Vec::new());
+        static SWITCH: RefCell<Option<UseStateHandle<bool>>> = RefCell::new(None);
+        static SWAP: RefCell<Option<UseStateHandle<u8>>> = RefCell::new(None);
+    }
+
+    #[component(Left)]
+    fn left() -> VNode {
+        let counter = use_state(0);
+        LEFT.with(|handle| handle.replace(Some(counter.clone())));
+
+        Div.with_child(format!("left {}", *counter)).into()
+    }
+
+    #[component(Right)]
+    fn right() -> VNode {
+        let counter = use_state(0);
+        RIGHT.with(|handle| handle.replace(Some(counter.clone())));
+
+        Div.with_child(format!("right {}", *counter)).into()
+    }
+
+    #[component(Pair)]
+    fn pair() -> VNode {
+        Div.with_child(Left(())).with_child(Right(())).into()
+    }
+
+    #[component]
+    fn frame(children: Children) -> VNode {
+        html! { <div class="frame">{for children}</div> }
+    }
+
+    #[component(Framed)]
+    fn framed() -> VNode {
+        let counter = use_state(0);
+        FRAMED.with(|handle| handle.replace(Some(counter.clone())));
+
+        html! {
+            <Frame>
+                {format!("framed {}", *counter)}
+                <Left />
+            </Frame>
+        }
+    }
+
+    #[component(Rows)]
+    fn rows() -> VNode {
+        let version = use_state(0);
+        ROWS.with(|handle| handle.replace(Some(version.clone())));
+        use_effect(
+            || {
+                ROWS_EFFECTS.with(|effects| effects.set(effects.get() + 1));
+                None::<fn()>
+            },
+            Some(*version),
+        );
+
+        let rows = (0..500)
+            .map(|row| Div.with_child(format!("{} {}", *version, row)).into())
+            .collect();
+        Div.with_child(Left(())).with_children(rows).into()
+    }
+
+    fn source_snapshot() -> u32 {
+        SOURCE.with(Cell::get)
+    }
+
+    #[component(Reader)]
+    fn reader(name: String) -> VNode {
+        let value = use_external_store(|_| || (), source_snapshot);
+        use_layout_effect(
+            move || {
+                PAINTED.with(|painted| painted.borrow_mut().push(value));
+                None::<fn()>
+            },
+            Some(value),
+        );
+
+        Div.with_child(format!("{} {}", name, value)).into()
+    }
+
+    #[component(Readers)]
+    fn readers() -> VNode {
+        let renders = use_state(0);
+        READERS.with(|handle| handle.replace(Some(renders.clone())));
+
+        Div.with_child(Reader::new("first".to_owned()))
+            .with_child(Reader::new("second".to_owned()))
+            .into()
+    }
+
+    #[component(Switch)]
+    fn switch() -> VNode {
+        let switched = use_state(false);
+        SWITCH.with(|handle| handle.replace(Some(switched.clone())));
+
+        if *switched {
+            Div.with_child(Right(())).into()
+        } else {
+            Div.with_child(Left(())).into()
+        }
+    }
+
+    struct Toggle(bool);
+
+    impl StructComponent for Toggle {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_: &()) -> Self {
+            Self(false)
+        }
+
+        fn update(&mut self, _: ()) -> ShouldRender {
+            self.0 = !self.0;
+            true
+        }
+
+        fn view(&self, link: &Link<Self>) -> VNode {
+            TOGGLE.with(|handle| handle.replace(Some(link.clone())));
+
+            if self.0 {
+                html! { <div>"on"<Right /></div> }
+            } else {
+                html! { <div>"off"</div> }
+            }
+        }
+    }
+
+    struct Label;
+
+    impl StructComponent for Label {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_: &()) -> Self {
+            Self
+        }
+
+        fn update(&mut self, _: ()) -> ShouldRender {
+            false
+        }
+
+        fn view(&self, _: &Link<Self>) -> VNode {
+            html! { <div>"label"</div> }
+        }
+    }
+
+    #[component(Swap)]
+    fn swap() -> VNode {
+        let position = use_state(0);
+        SWAP.with(|handle| handle.replace(Some(position.clone())));
+
+        match *position {
+            0 => Div.with_child(StructNode::<Toggle>::new(())).into(),
+            1 => Div.with_child(Left(())).into(),
+            _ => Div.with_child(StructNode::<Label>::new(())).into(),
+        }
+    }
+
+    fn mount(element: VNode) -> (App, FiberId) {
+        let mut fiber_tree = FiberTree::default();
+        let root_id = fiber_tree.new_node(Node::Element(Element {
+            dom: None,
+            tag: Tag::Empty,
+            attributes: HashMap::with_capacity(0),
+            events: Events(HashMap::with_capacity(0)),
+            unprocessed_children: vec![element],
+        }));
+        let mut app = App {
+            fiber_tree,
+            next_unit_of_work: Some(root_id),
+            wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
+            document: None,
+        };
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        (app, root_id)
+    }
+
+    /// The committed texts of the tree, in order
+    fn texts(app: &App, root_id: FiberId) -> Vec<String> {
+        let mut texts = Vec::new();
+        let mut stack = vec![Some(root_id)];
+        while let Some(id) = stack.pop() {
+            let mut child_id = id
+                .and_then(|id| app.fiber_tree.get(id))
+                .and_then(|node| node.child);
+            let mut children = Vec::new();
+            while let Some(some_child_id) = child_id {
+                children.push(Some(some_child_id));
+                child_id = app
+                    .fiber_tree
+                    .get(some_child_id)
+                    .and_then(|node| node.sibling);
+            }
+            stack.extend(children.into_iter().rev());
+
+            if let Some(FiberNode {
+                node: Node::Text(Text { text, .. }),
+                ..
+            }) = id.and_then(|id| app.fiber_tree.get(id))
             {
-                commit(Some(child_id), &mut app.fiber_tree);
-                app.wip_root = None;
+                texts.push(text.clone());
             }
         }
+
+        texts
+    }
+
+    /// Performs `units` units of work of the next update
+    fn render_partially(app: &mut App, units: usize) {
+        let remaining = Cell::new(units);
+        start_next_update(app);
+        work_loop(app, || {
+            let units = remaining.get();
+            remaining.set(units.saturating_sub(1));
+            units > 0
+        });
+        assert!(app.next_unit_of_work.is_some());
+    }
+
+    fn finish_work(app: &mut App) {
+        start_next_update(app);
+        work_loop(app, || true);
+        commit_work(app, || true);
+    }
+
+    #[test]
+    fn restart_render_on_newer_update() {
+        let (mut app, root_id) = mount(Left(()).into());
+        take_scheduled_updates();
+
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        render_partially(&mut app, 2);
+
+        // A partial render is never committed
+        commit_work(&mut app, || true);
+        assert_eq!(texts(&app, root_id), vec!["left 0"]);
+
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(2));
+        finish_work(&mut app);
+
+        assert_eq!(texts(&app, root_id), vec!["left 2"]);
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn restart_from_common_component() {
+        let (mut app, root_id) = mount(Pair(()).into());
+        take_scheduled_updates();
+
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        render_partially(&mut app, 1);
+
+        RIGHT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+
+        assert_eq!(texts(&app, root_id), vec!["left 1", "right 1"]);
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn commit_visits_only_rendered_components() {
+        let (mut app, root_id) = mount(Rows(()).into());
+        take_scheduled_updates();
+        let rows_id = app.fiber_tree.get(root_id).and_then(|n| n.child).unwrap();
+        assert_eq!(ROWS_EFFECTS.with(Cell::get), 1);
+
+        ROWS.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        start_next_update(&mut app);
+        work_loop(&mut app, || true);
+
+        // The effects of the update visit its two components, not the thousand fibers of the rows
+        assert!(app.fiber_tree.len() > 1000);
+        assert_eq!(app.rendered.len(), 2);
+        assert_eq!(app.rendered.last(), Some(&rows_id));
+
+        commit_work(&mut app, || true);
+        assert!(app.rendered.is_empty());
+        assert_eq!(ROWS_EFFECTS.with(Cell::get), 2);
+        assert_eq!(texts(&app, root_id)[1], "1 0");
+    }
+
+    #[test]
+    fn torn_render_is_never_committed() {
+        let (mut app, root_id) = mount(Readers(()).into());
+        take_scheduled_updates();
+        assert_eq!(PAINTED.with(|painted| painted.take()), vec![0, 0]);
+
+        READERS.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        // Readers, its div and the first reader
+        render_partially(&mut app, 3);
+
+        // The source changes in the middle of the render, before it notifies the subscribers
+        SOURCE.with(|source| source.set(1));
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        assert_eq!(texts(&app, root_id), vec!["first 1", "second 1"]);
+        assert_eq!(PAINTED.with(|painted| painted.take()), vec![1, 1]);
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn children_are_reconciled() {
+        let (mut app, root_id) = mount(Framed(()).into());
+        take_scheduled_updates();
+        assert_eq!(texts(&app, root_id), vec!["framed 0", "left 0"]);
+
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+        FRAMED.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+
+        // The child component keeps its state when the component that passes it is rendered again
+        assert_eq!(texts(&app, root_id), vec!["framed 1", "left 1"]);
+    }
+
+    #[test]
+    fn struct_components_with_function_components() {
+        let (mut app, root_id) = mount(
+            Div.with_child(Left(()))
+                .with_child(StructNode::<Toggle>::new(()))
+                .into(),
+        );
+        take_scheduled_updates();
+        assert_eq!(texts(&app, root_id), vec!["left 0", "off"]);
+
+        TOGGLE.with(|handle| handle.borrow().as_ref().unwrap().send_message(()));
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+        finish_work(&mut app);
+
+        assert_eq!(texts(&app, root_id), vec!["left 1", "on", "right 0"]);
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn swapped_components_are_mounted_again() {
+        let (mut app, root_id) = mount(Switch(()).into());
+        take_scheduled_updates();
+
+        LEFT.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["left 1"]);
+
+        // Another component at the same position gets its own hooks
+        SWITCH.with(|handle| handle.borrow().as_ref().unwrap().set(true));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["right 0"]);
+
+        SWITCH.with(|handle| handle.borrow().as_ref().unwrap().set(false));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["left 0"]);
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn swapped_struct_components_are_mounted_again() {
+        let (mut app, root_id) = mount(Swap(()).into());
+        take_scheduled_updates();
+        assert_eq!(texts(&app, root_id), vec!["off"]);
+
+        SWAP.with(|handle| handle.borrow().as_ref().unwrap().set(1));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["left 0"]);
+
+        SWAP.with(|handle| handle.borrow().as_ref().unwrap().set(0));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["off"]);
+
+        // Another struct component doesn't reuse the state of the previous one
+        SWAP.with(|handle| handle.borrow().as_ref().unwrap().set(2));
+        finish_work(&mut app);
+        assert_eq!(texts(&app, root_id), vec!["label"]);
+        assert!(take_scheduled_updates().is_empty());
     }
 }
@@ -0,0 +1,163 @@
+use crate::Event;
+use std::fmt::Debug;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// A shared handler that a component passes to its children, like an `on_click` prop.
+///
+/// Cloning a callback is cheap, and two callbacks are equal only when they are clones of the
+/// same handler, so props with callbacks can be compared.
+///
+/// A callback whose input is an [EventArgument] can be attached to an element as an event.
+///
+/// # Example
+/// ```rust
+/// # use review::{component, html, log, Callback, VNode};
+/// #[component]
+/// pub fn square(index: usize, on_click: Callback<usize>) -> VNode {
+///     html! { <button on_click={on_click.reform(move |_: ()| index)}>{index.to_string()}</button> }
+/// }
+///
+/// let on_click = Callback::from(|index: usize| log::info!("clicked {}", index));
+/// let square = html! { <Square index={3} on_click={on_click.clone()} /> };
+///
+/// on_click.emit(3);
+/// assert_eq!(on_click, on_click.clone());
+/// ```
+pub struct Callback<IN, OUT = ()> {
+    function: Rc<dyn Fn(IN) -> OUT>,
+}
+
+impl<IN, OUT> Callback<IN, OUT> {
+    /// Calls the handler with `value`
+    pub fn emit(&self, value: IN) -> OUT {
+        (self.function)(value)
+    }
+}
+
+impl<IN: 'static, OUT: 'static> Callback<IN, OUT> {
+    /// Creates a new callback that maps its input with `function` before calling this one
+    pub fn reform<T, F>(&self, function: F) -> Callback<T, OUT>
+    where
+        F: Fn(T) -> IN + 'static,
+    {
+        let callback = self.clone();
+
+        Callback::from(move |value| callback.emit(function(value)))
+    }
+}
+
+impl<IN, OUT, F: Fn(IN) -> OUT + 'static> From<F> for Callback<IN, OUT> {
+    fn from(function: F) -> Self {
+        Self {
+            function: Rc::new(function),
+        }
+    }
+}
+
+impl<IN, OUT> Clone for Callback<IN, OUT> {
+    fn clone(&self) -> Self {
+        Self {
+            function: self.function.clone(),
+        }
+    }
+}
+
+impl<IN, OUT> PartialEq for Callback<IN, OUT> {
+    fn eq(&self, other: &Self) -> bool {
+        // Only the data pointers are compared, the vtables of the same closure can differ
+        Rc::as_ptr(&self.function) as *const u8 == Rc::as_ptr(&other.function) as *const u8
+    }
+}
+
+impl<IN, OUT> Debug for Callback<IN, OUT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Callback").finish()
+    }
+}
+
+/// The input of a [Callback] attached to an element as an event, created from the js event
+pub trait EventArgument: Sized + 'static {
+    fn from_event(event: JsValue) -> Self;
+}
+
+impl EventArgument for () {
+    fn from_event(_: JsValue) -> Self {}
+}
+
+impl EventArgument for JsValue {
+    fn from_event(event: JsValue) -> Self {
+        event
+    }
+}
+
+macro_rules! event_argument {
+    ( $( $event:ty ),* ) => {
+        $(
+            impl EventArgument for $event {
+                fn from_event(event: JsValue) -> Self {
+                    event.unchecked_into()
+                }
+            }
+        )*
+    };
+}
+
+event_argument!(web_sys::Event, web_sys::UiEvent, web_sys::MouseEvent);
+
+impl<IN: EventArgument, OUT: 'static> From<Callback<IN, OUT>> for Event {
+    fn from(callback: Callback<IN, OUT>) -> Self {
+        Rc::new(Closure::wrap(Box::new(move |event: JsValue| {
+            callback.emit(IN::from_event(event));
+        }) as Box<dyn Fn(JsValue)>))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn emit_and_reform() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let callback = Callback::from({
+            let received = received.clone();
+            move |value: usize| {
+                received.borrow_mut().push(value);
+                value * 2
+            }
+        });
+
+        assert_eq!(callback.emit(1), 2);
+
+        let reformed = callback.reform(|text: &str| text.len());
+        assert_eq!(reformed.emit("abc"), 6);
+
+        assert_eq!(*received.borrow(), vec![1, 3]);
+    }
+
+    #[test]
+    fn macro_clones() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let callback: Callback<(usize, usize)> = crate::callback!(Callback, [received] move |(first, second)| {
+            received.borrow_mut().push(first + second)
+        });
+
+        callback.emit((1, 2));
+        received.borrow_mut().push(0);
+
+        assert_eq!(*received.borrow(), vec![3, 0]);
+    }
+
+    #[test]
+    fn pointer_equality() {
+        let callback = Callback::from(|_: ()| {});
+        let same_closure = Callback::from(|_: ()| {});
+
+        assert_eq!(callback, callback.clone());
+        assert_ne!(callback, same_closure);
+        assert_ne!(callback.reform(|_: ()| ()), callback);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{ComponentProvider, VNode};
+
+/// The child nodes passed to a component by its caller.
+///
+/// A props field named `children` with this type lets a component wrap arbitrary content,
+/// set with [ComponentBuilder::with_children] or with the children of the component in the [html!](crate::html) macro.
+///
+/// # Example
+/// ```rust
+/// # use review::{children, component, html, Children, ComponentBuilder, ElementBuilder, VNode};
+/// # use review::Tag::{Div, P};
+/// #[component]
+/// pub fn card(title: String, children: Children) -> VNode {
+///     html! {
+///         <div class="card">
+///             <h2>{title}</h2>
+///             {for children}
+///         </div>
+///     }
+/// }
+///
+/// let card: VNode = Card::new("Welcome".to_owned(), Default::default())
+///     .with_children(children!(P.with_child("Hello"), "World"))
+///     .into();
+/// let same_card = html! {
+///     <Card title={"Welcome".to_owned()}>
+///         <p>"Hello"</p>
+///         "World"
+///     </Card>
+/// };
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Children(Vec<VNode>);
+
+impl Children {
+    /// Returns an iterator over the child nodes
+    pub fn iter(&self) -> std::slice::Iter<'_, VNode> {
+        self.0.iter()
+    }
+
+    /// Returns the number of child nodes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no child nodes
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the child nodes as a vector, ready to be passed to [ElementBuilder::with_children](crate::ElementBuilder::with_children)
+    pub fn to_vec(&self) -> Vec<VNode> {
+        self.0.clone()
+    }
+}
+
+impl From<Vec<VNode>> for Children {
+    fn from(children: Vec<VNode>) -> Self {
+        Self(children)
+    }
+}
+
+impl From<Children> for Vec<VNode> {
+    fn from(children: Children) -> Self {
+        children.0
+    }
+}
+
+impl IntoIterator for Children {
+    type Item = VNode;
+    type IntoIter = std::vec::IntoIter<VNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Children {
+    type Item = &'a VNode;
+    type IntoIter = std::slice::Iter<'a, VNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Props with a `children` field, implemented by `#[derive(Properties)]` and by the props
+/// generated by the [component](crate::component) attribute
+pub trait ChildrenProps {
+    /// A mutable reference to the children of the props
+    fn children_mut(&mut self) -> &mut Children;
+}
+
+/// Attaches children to a component whose props have a `children` field
+pub trait ComponentBuilder: Sized {
+    /// This function is used to append a child that implements [Into<VNode>] to a component
+    fn with_child<T: Into<VNode>>(self, child: T) -> Self;
+
+    /// This function is used to append a list of children [VNode] to a component.
+    /// The [children!](crate::children) macro avoids the `.into()` calls
+    fn with_children(self, children: Vec<VNode>) -> Self;
+}
+
+impl<T: ComponentProvider> ComponentBuilder for T
+where
+    T::Props: ChildrenProps,
+{
+    fn with_child<C: Into<VNode>>(mut self, child: C) -> Self {
+        self.get_mut_props().children_mut().0.push(child.into());
+        self
+    }
+
+    fn with_children(mut self, children: Vec<VNode>) -> Self {
+        self.get_mut_props().children_mut().0.extend(children);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as review;
+    use crate::{children, component, html};
+
+    #[component]
+    fn frame(children: Children) -> VNode {
+        html! { <div>{for children}</div> }
+    }
+
+    #[test]
+    fn builder_appends_children() {
+        let frame = Frame::new(Children::default())
+            .with_child("a")
+            .with_children(children!("b", "c"));
+
+        assert_eq!(frame.0.children.len(), 3);
+        assert_eq!(frame.0.children.to_vec(), children!("a", "b", "c"));
+    }
+}
@@ -13,6 +13,8 @@ pub trait ComponentProvider: Debug {
     fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode;
 
     fn get_props(&self) -> &Self::Props;
+
+    fn get_mut_props(&mut self) -> &mut Self::Props;
 }
 
 #[doc(hidden)]
@@ -24,12 +26,9 @@ pub trait AnyComponent: Debug {
 
 impl<T: Any + ComponentProvider> AnyComponent for T {
     fn render(&self, context: &mut (FiberId, &mut HookContext)) -> VNode {
-        context.1.counter = 0;
+        context.1.start_render(std::any::type_name::<T>());
         let node = T::render(context, self.get_props());
-
-        for h in context.1.hooks.iter_mut() {
-            h.post_render();
-        }
+        context.1.finish_render();
 
         node
     }
@@ -50,3 +49,40 @@ impl<T: Any + ComponentProvider> From<T> for VNode {
         VNode::Component(Rc::new(v))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate as review;
+    use crate::Tag::Ul;
+    use crate::{component, html, ElementBuilder, HookContext, VNode};
+
+    #[component]
+    fn item_list(mut items: Vec<String>, sorted: bool) -> VNode {
+        if sorted {
+            items.sort();
+        }
+
+        html! { <ul>{for items}</ul> }
+    }
+
+    fn render(component: ItemList) -> VNode {
+        review::AnyComponent::render(&component, &mut (0.into(), &mut HookContext::default()))
+    }
+
+    #[test]
+    fn named_arguments() {
+        let items = vec!["b".to_owned(), "a".to_owned()];
+
+        assert_eq!(
+            render(ItemList::new(items.clone(), true)),
+            Ul.with_children(vec!["a".into(), "b".into()]).into()
+        );
+        assert_eq!(
+            render(ItemList(ItemListProps {
+                items,
+                sorted: false
+            })),
+            Ul.with_children(vec!["b".into(), "a".into()]).into()
+        );
+    }
+}
@@ -1,12 +1,31 @@
-use crate::node::{Element, Node};
+use crate::node::{Component, Element, Node, Text};
 use crate::vdom::VElement;
 use crate::AnyComponent;
 use crate::{Events, VNode};
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// The id of a fiber in the [FiberTree].
+///
+/// It's made of the index of the slot in the tree and the generation of the slot, which is
+/// increased every time the slot is reused, so the id of a removed fiber never points to another
+/// fiber.
 #[doc(hidden)]
-pub type FiberId = usize;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FiberId {
+    index: usize,
+    generation: u32,
+}
+
+#[cfg(test)]
+impl From<usize> for FiberId {
+    fn from(index: usize) -> Self {
+        Self {
+            index,
+            generation: 0,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct FiberNode {
@@ -14,8 +33,9 @@ pub(crate) struct FiberNode {
     pub child: Option<FiberId>,
     pub parent: Option<FiberId>,
     pub sibling: Option<FiberId>,
+    /// The previous sibling, so a fiber is unlinked without scanning the children of its parent
+    pub prev_sibling: Option<FiberId>,
     pub effect_tag: Option<EffectTag>,
-    pub state: State,
 }
 
 #[derive(Debug)]
@@ -67,51 +87,120 @@ impl std::cmp::PartialEq<VNode> for FiberNode {
                 VNode::Element(VElement { tag: vnode_tag, .. }),
             ) => node_tag == vnode_tag,
             (Node::Text(_), VNode::Text(_)) => true,
-            (Node::Component(_), VNode::Component(_)) => true,
+            // A component of another type has other hooks, so it can't reuse the fiber
+            (Node::Component(Component { function, .. }), VNode::Component(vcomponent)) => {
+                function.get_type() == vcomponent.get_type()
+            }
             _ => false,
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
-pub(crate) enum State {
-    Valid,
-    Removed,
+// The free slots are reused by the next fibers, so boxing the fibers would only add an allocation
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Occupied(FiberNode),
+    Free { next_free_slot: Option<usize> },
+}
+
+#[derive(Debug, PartialEq)]
+struct Slot {
+    generation: u32,
+    entry: Entry,
 }
 
+/// The arena that owns the fibers.
+///
+/// The free slots are linked in a free list, so fibers are allocated and removed in constant time.
+/// Removing a fiber drops it, together with its DOM node, events and hooks, and increases the
+/// generation of its slot so its [FiberId] is no longer valid.
 #[derive(Default, PartialEq, Debug)]
 pub(crate) struct FiberTree {
-    nodes: Vec<FiberNode>,
-    first_free_node: Option<FiberId>,
+    slots: Vec<Slot>,
+    first_free_slot: Option<usize>,
+    free_slots: usize,
+    /// The generation of the slots pushed after the tree is compacted
+    next_generation: u32,
 }
 
 impl FiberTree {
     pub fn new_node(&mut self, node: Node) -> FiberId {
-        let new_fiber_node = FiberNode {
+        let fiber_node = FiberNode {
             child: None,
             sibling: None,
+            prev_sibling: None,
             parent: None,
-            state: State::Valid,
             effect_tag: Some(EffectTag::Placement),
             node,
         };
-        if let Some(first_free_node) = self.first_free_node {
-            self.nodes[first_free_node] = new_fiber_node;
-            let id = first_free_node;
-            self.first_free_node = self.nodes.iter().position(|n| n.state == State::Removed);
-            id
-        } else {
-            self.nodes.push(new_fiber_node);
-            self.nodes.len() - 1
+
+        match self.first_free_slot {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                if let Entry::Free { next_free_slot } = slot.entry {
+                    self.first_free_slot = next_free_slot;
+                }
+                slot.entry = Entry::Occupied(fiber_node);
+                self.free_slots -= 1;
+
+                FiberId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: self.next_generation,
+                    entry: Entry::Occupied(fiber_node),
+                });
+
+                FiberId {
+                    index: self.slots.len() - 1,
+                    generation: self.next_generation,
+                }
+            }
         }
     }
 
+    /// Returns the fiber with the given id, or `None` if it was removed
     pub fn get(&self, id: FiberId) -> Option<&FiberNode> {
-        self.nodes.get(id)
+        match self.slots.get(id.index) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(fiber_node),
+            }) if *generation == id.generation => Some(fiber_node),
+            _ => None,
+        }
     }
 
+    /// Like [FiberTree::get] but returns a mutable reference
     pub fn get_mut(&mut self, id: FiberId) -> Option<&mut FiberNode> {
-        self.nodes.get_mut(id)
+        match self.slots.get_mut(id.index) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(fiber_node),
+            }) if *generation == id.generation => Some(fiber_node),
+            _ => None,
+        }
+    }
+
+    /// The number of fibers in the tree
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots
+    }
+
+    pub fn is_ancestor(&self, ancestor_id: FiberId, id: FiberId) -> bool {
+        let mut parent_id = self.get(id).and_then(|node| node.parent);
+        while let Some(some_parent_id) = parent_id {
+            if some_parent_id == ancestor_id {
+                return true;
+            }
+            parent_id = self.get(some_parent_id).and_then(|node| node.parent);
+        }
+
+        false
     }
 
     pub fn insert_child(
@@ -120,97 +209,176 @@ impl FiberTree {
         parent_id: FiberId,
         after_id: Option<FiberId>,
     ) {
-        if let Some(after_id) = after_id {
-            if let Some(old_sibling) = self
-                .nodes
+        let next_sibling = match after_id {
+            Some(after_id) => self
                 .get_mut(after_id)
-                .and_then(|after| after.sibling.replace(child_id))
-            {
-                if let Some(child) = self.nodes.get_mut(child_id) {
-                    child.sibling = Some(old_sibling)
-                }
-            }
-        } else if let Some(old_child) = self
-            .nodes
-            .get_mut(parent_id)
-            .and_then(|parent| parent.child.replace(child_id))
-        {
-            if let Some(child) = self.nodes.get_mut(child_id) {
-                child.sibling = Some(old_child)
-            }
+                .and_then(|after| after.sibling.replace(child_id)),
+            None => self
+                .get_mut(parent_id)
+                .and_then(|parent| parent.child.replace(child_id)),
+        };
+
+        if let Some(next_sibling) = next_sibling.and_then(|id| self.get_mut(id)) {
+            next_sibling.prev_sibling = Some(child_id);
         }
 
-        if let Some(child) = self.nodes.get_mut(child_id) {
+        if let Some(child) = self.get_mut(child_id) {
             child.parent = Some(parent_id);
+            child.sibling = next_sibling;
+            child.prev_sibling = after_id;
         }
     }
 
+    /// Removes a fiber and all its descendants from the tree, dropping them
     pub fn remove(&mut self, id: FiberId) {
-        let reference = self.get(id).map(|node| (node.parent, node.sibling));
-        if let Some((Some(parent_id), sibling_id)) = reference {
-            if let Some(first_child) = self.get(parent_id).and_then(|node| node.child) {
-                if first_child == id {
-                    if let Some(parent) = self.get_mut(parent_id) {
-                        parent.child = sibling_id
-                    }
-                } else {
-                    let mut prev_sibling = Some(first_child);
-                    while let Some(some_prev_sibling) = prev_sibling {
-                        let current = self.get(some_prev_sibling).and_then(|node| node.sibling);
-                        if current == Some(id) {
-                            break;
-                        } else {
-                            prev_sibling = current;
-                        }
-                    }
-                    if let Some(prev_sibling) = prev_sibling {
-                        if let Some(prev_sibling) = self.get_mut(prev_sibling) {
-                            prev_sibling.sibling = sibling_id
-                        }
-                    }
+        let (parent_id, sibling_id, prev_sibling_id) = match self.get(id) {
+            Some(fiber_node) => (
+                fiber_node.parent,
+                fiber_node.sibling,
+                fiber_node.prev_sibling,
+            ),
+            None => return,
+        };
+
+        match prev_sibling_id {
+            Some(prev_sibling_id) => {
+                if let Some(prev_sibling) = self.get_mut(prev_sibling_id) {
+                    prev_sibling.sibling = sibling_id
+                }
+            }
+            None => {
+                if let Some(parent) = parent_id.and_then(|parent_id| self.get_mut(parent_id)) {
+                    parent.child = sibling_id
                 }
             }
         }
-
-        if self.first_free_node.is_none() {
-            self.first_free_node = Some(id);
+        if let Some(sibling) = sibling_id.and_then(|sibling_id| self.get_mut(sibling_id)) {
+            sibling.prev_sibling = prev_sibling_id
         }
 
-        if let Some(node) = self.get_mut(id) {
-            node.state = State::Removed;
-            let mut current_id = node.child;
-            while let Some(some_current_id) = current_id {
-                if let Some(node) = self.get_mut(some_current_id) {
-                    node.state = State::Removed;
-
-                    if node.child.is_some() {
-                        current_id = node.child;
-                    } else if node.sibling.is_some() {
-                        current_id = node.sibling;
-                    } else if let Some(parent) = node.parent {
-                        if parent != id {
-                            current_id =
-                                self.get(parent).and_then(|parent_node| parent_node.sibling);
-                        } else {
-                            current_id = None;
-                        }
-                    } else {
-                        current_id = None;
-                    }
+        let mut stack = vec![id];
+        while let Some(current_id) = stack.pop() {
+            if let Some(fiber_node) = self.free(current_id) {
+                stack.extend(fiber_node.child);
+                if current_id != id {
+                    stack.extend(fiber_node.sibling);
                 }
             }
         }
     }
+
+    fn free(&mut self, id: FiberId) -> Option<FiberNode> {
+        self.get(id)?;
+
+        let slot = &mut self.slots[id.index];
+        slot.generation = slot.generation.wrapping_add(1);
+        let entry = std::mem::replace(
+            &mut slot.entry,
+            Entry::Free {
+                next_free_slot: self.first_free_slot,
+            },
+        );
+        self.first_free_slot = Some(id.index);
+        self.free_slots += 1;
+
+        match entry {
+            Entry::Occupied(fiber_node) => Some(fiber_node),
+            Entry::Free { .. } => None,
+        }
+    }
+
+    /// Releases the memory of the free slots at the end of the tree.
+    ///
+    /// The fibers are never moved, so their ids stay valid.
+    pub fn compact(&mut self) {
+        while let Some(Slot {
+            generation,
+            entry: Entry::Free { .. },
+        }) = self.slots.last()
+        {
+            self.next_generation = self.next_generation.max(*generation);
+            self.slots.pop();
+            self.free_slots -= 1;
+        }
+        self.slots.shrink_to_fit();
+
+        self.first_free_slot = None;
+        for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+            if let Entry::Free { next_free_slot } = &mut slot.entry {
+                *next_free_slot = self.first_free_slot;
+                self.first_free_slot = Some(index);
+            }
+        }
+    }
+}
+
+/// A tree of text fibers without DOM nodes, used by the benchmarks in `benches/`
+#[doc(hidden)]
+#[derive(Default)]
+pub struct BenchTree(FiberTree);
+
+impl BenchTree {
+    /// Adds a tree of `size` fibers, where every tenth fiber is the parent of the next ten
+    pub fn create_tree(&mut self, size: usize) -> FiberId {
+        let root_id = self.new_node();
+        let mut parent_id = root_id;
+        for index in 1..size {
+            let id = self.new_node();
+            self.0.insert_child(id, parent_id, None);
+            if index % 10 == 0 {
+                parent_id = id;
+            }
+        }
+
+        root_id
+    }
+
+    /// Adds a fiber with `count` children, returned from the last to the first
+    pub fn create_children(&mut self, count: usize) -> (FiberId, Vec<FiberId>) {
+        let parent_id = self.new_node();
+        let children = (0..count)
+            .map(|_| {
+                let id = self.new_node();
+                self.0.insert_child(id, parent_id, None);
+                id
+            })
+            .collect();
+
+        (parent_id, children)
+    }
+
+    pub fn remove(&mut self, id: FiberId) {
+        self.0.remove(id);
+    }
+
+    pub fn compact(&mut self) {
+        self.0.compact();
+    }
+
+    fn new_node(&mut self) -> FiberId {
+        self.0.new_node(Node::Text(Text {
+            dom: None,
+            text: String::new(),
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::FiberTree;
+    use super::{FiberId, FiberTree};
     use crate::{
-        fiber::{EffectTag, FiberNode, State},
-        node::{Node, Text},
+        fiber::{EffectTag, Entry, FiberNode, Slot},
+        node::{Element, Node, Text},
+        ElementBuilder, Tag, VNode,
     };
 
+    fn occupied(fiber_node: FiberNode) -> Slot {
+        Slot {
+            generation: 0,
+            entry: Entry::Occupied(fiber_node),
+        }
+    }
+
     fn create_test_node(string: &str) -> Node {
         Node::Text(Text {
             dom: None,
@@ -227,22 +395,50 @@ mod tests {
         assert_eq!(
             fiber_tree,
             FiberTree {
-                nodes: vec!(FiberNode {
+                slots: vec!(occupied(FiberNode {
                     child: None,
                     sibling: None,
+                    prev_sibling: None,
                     parent: None,
-                    state: State::Valid,
                     effect_tag: Some(EffectTag::Placement),
                     node: Node::Text(Text {
                         dom: None,
                         text: "test".to_owned(),
                     })
-                }),
-                first_free_node: None
+                })),
+                first_free_slot: None,
+                free_slots: 0,
+                next_generation: 0,
             }
         )
     }
 
+    #[test]
+    fn equality_with_custom_tags() {
+        let fiber_node = FiberNode {
+            child: None,
+            sibling: None,
+            prev_sibling: None,
+            parent: None,
+            effect_tag: None,
+            node: Node::Element(Element {
+                dom: None,
+                tag: Tag::custom("my-widget"),
+                attributes: Default::default(),
+                events: Default::default(),
+                unprocessed_children: Vec::new(),
+            }),
+        };
+
+        let same_tag: VNode = Tag::custom(String::from("my-widget"))
+            .with_child("text")
+            .into();
+        let other_tag: VNode = Tag::custom("my-other-widget").into();
+        assert_eq!(fiber_node, same_tag);
+        assert_ne!(fiber_node, other_tag);
+        assert_ne!(fiber_node, VNode::from(Tag::Div));
+    }
+
     #[test]
     fn inser_child() {
         let mut fiber_tree = FiberTree::default();
@@ -255,31 +451,33 @@ mod tests {
         assert_eq!(
             fiber_tree,
             FiberTree {
-                nodes: vec!(
-                    FiberNode {
+                slots: vec!(
+                    occupied(FiberNode {
                         child: Some(child_id),
                         sibling: None,
+                        prev_sibling: None,
                         parent: None,
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test".to_owned(),
                         })
-                    },
-                    FiberNode {
+                    }),
+                    occupied(FiberNode {
                         child: None,
                         sibling: None,
+                        prev_sibling: None,
                         parent: Some(parent_id),
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test child".to_owned(),
                         })
-                    }
+                    })
                 ),
-                first_free_node: None
+                first_free_slot: None,
+                free_slots: 0,
+                next_generation: 0,
             }
         )
     }
@@ -298,42 +496,44 @@ mod tests {
         assert_eq!(
             fiber_tree,
             FiberTree {
-                nodes: vec!(
-                    FiberNode {
+                slots: vec!(
+                    occupied(FiberNode {
                         child: Some(child_1_id),
                         sibling: None,
+                        prev_sibling: None,
                         parent: None,
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test".to_owned(),
                         })
-                    },
-                    FiberNode {
+                    }),
+                    occupied(FiberNode {
                         child: None,
                         sibling: Some(child_2_id),
+                        prev_sibling: None,
                         parent: Some(parent_id),
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test child 1".to_owned(),
                         })
-                    },
-                    FiberNode {
+                    }),
+                    occupied(FiberNode {
                         child: None,
                         sibling: None,
+                        prev_sibling: Some(child_1_id),
                         parent: Some(parent_id),
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test child 2".to_owned(),
                         })
-                    }
+                    })
                 ),
-                first_free_node: None
+                first_free_slot: None,
+                free_slots: 0,
+                next_generation: 0,
             }
         )
     }
@@ -356,53 +556,50 @@ mod tests {
         assert_eq!(
             fiber_tree,
             FiberTree {
-                nodes: vec!(
-                    FiberNode {
+                slots: vec!(
+                    occupied(FiberNode {
                         child: Some(child_2_id),
                         sibling: None,
+                        prev_sibling: None,
                         parent: None,
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test".to_owned(),
                         })
+                    }),
+                    Slot {
+                        generation: 1,
+                        entry: Entry::Free {
+                            next_free_slot: None
+                        }
                     },
-                    FiberNode {
-                        child: None,
-                        sibling: Some(child_2_id),
-                        parent: Some(parent_id),
-                        state: State::Removed,
-                        effect_tag: Some(EffectTag::Placement),
-                        node: Node::Text(Text {
-                            dom: None,
-                            text: "test child 1".to_owned(),
-                        })
-                    },
-                    FiberNode {
+                    occupied(FiberNode {
                         child: None,
                         sibling: Some(child_3_id),
+                        prev_sibling: None,
                         parent: Some(parent_id),
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test child 2".to_owned(),
                         })
-                    },
-                    FiberNode {
+                    }),
+                    occupied(FiberNode {
                         child: None,
                         sibling: None,
+                        prev_sibling: Some(child_2_id),
                         parent: Some(parent_id),
-                        state: State::Valid,
                         effect_tag: Some(EffectTag::Placement),
                         node: Node::Text(Text {
                             dom: None,
                             text: "test child 3".to_owned(),
                         })
-                    }
+                    })
                 ),
-                first_free_node: Some(child_1_id)
+                first_free_slot: Some(child_1_id.index),
+                free_slots: 1,
+                next_generation: 0,
             }
         )
     }
@@ -421,8 +618,8 @@ mod tests {
             Some(&FiberNode {
                 child: Some(child_id),
                 sibling: None,
+                prev_sibling: None,
                 parent: None,
-                state: State::Valid,
                 effect_tag: Some(EffectTag::Placement),
                 node: Node::Text(Text {
                     dom: None,
@@ -435,8 +632,8 @@ mod tests {
             Some(&FiberNode {
                 child: None,
                 sibling: None,
+                prev_sibling: None,
                 parent: Some(parent_id),
-                state: State::Valid,
                 effect_tag: Some(EffectTag::Placement),
                 node: Node::Text(Text {
                     dom: None,
@@ -461,8 +658,8 @@ mod tests {
             Some(&FiberNode {
                 child: None,
                 sibling: None,
+                prev_sibling: None,
                 parent: Some(parent_id),
-                state: State::Valid,
                 effect_tag: Some(EffectTag::Placement),
                 node: Node::Text(Text {
                     dom: None,
@@ -489,8 +686,8 @@ mod tests {
             Some(&FiberNode {
                 child: None,
                 sibling: None,
+                prev_sibling: Some(child_id),
                 parent: Some(parent_id),
-                state: State::Valid,
                 effect_tag: Some(EffectTag::Placement),
                 node: Node::Text(Text {
                     dom: None,
@@ -515,8 +712,8 @@ mod tests {
             Some(&FiberNode {
                 child: Some(child_id),
                 sibling: None,
+                prev_sibling: None,
                 parent: None,
-                state: State::Valid,
                 effect_tag: Some(EffectTag::Placement),
                 node: Node::Text(Text {
                     dom: None,
@@ -525,4 +722,149 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn stale_id_after_slot_reuse() {
+        let mut fiber_tree = FiberTree::default();
+
+        let parent_id = fiber_tree.new_node(create_test_node("test"));
+        let child_id = fiber_tree.new_node(create_test_node("test child"));
+        fiber_tree.insert_child(child_id, parent_id, None);
+
+        fiber_tree.remove(child_id);
+        let new_child_id = fiber_tree.new_node(create_test_node("new test child"));
+
+        assert_eq!(new_child_id.index, child_id.index);
+        assert_ne!(new_child_id, child_id);
+        assert!(fiber_tree.get(child_id).is_none());
+        assert!(fiber_tree.get_mut(child_id).is_none());
+        assert!(fiber_tree.get(new_child_id).is_some());
+    }
+
+    #[test]
+    fn remove_subtree() {
+        let mut fiber_tree = FiberTree::default();
+
+        let root_id = fiber_tree.new_node(create_test_node("root"));
+        let parent_id = fiber_tree.new_node(create_test_node("parent"));
+        let sibling_id = fiber_tree.new_node(create_test_node("sibling"));
+        let child_1_id = fiber_tree.new_node(create_test_node("child 1"));
+        let child_2_id = fiber_tree.new_node(create_test_node("child 2"));
+
+        fiber_tree.insert_child(parent_id, root_id, None);
+        fiber_tree.insert_child(sibling_id, root_id, Some(parent_id));
+        fiber_tree.insert_child(child_1_id, parent_id, None);
+        fiber_tree.insert_child(child_2_id, parent_id, Some(child_1_id));
+
+        fiber_tree.remove(parent_id);
+
+        assert_eq!(fiber_tree.len(), 2);
+        assert_eq!(fiber_tree.get(root_id).unwrap().child, Some(sibling_id));
+        for id in [parent_id, child_1_id, child_2_id] {
+            assert!(fiber_tree.get(id).is_none());
+        }
+
+        let reused_ids: Vec<FiberId> = (0..3)
+            .map(|_| fiber_tree.new_node(create_test_node("new")))
+            .collect();
+        assert_eq!(fiber_tree.slots.len(), 5);
+        for (reused_id, removed_id) in reused_ids.iter().zip([parent_id, child_1_id, child_2_id]) {
+            assert!(fiber_tree.get(removed_id).is_none());
+            assert!(fiber_tree.get(*reused_id).is_some());
+        }
+    }
+
+    #[test]
+    fn compact_trailing_free_slots() {
+        let mut fiber_tree = FiberTree::default();
+
+        let root_id = fiber_tree.new_node(create_test_node("root"));
+        let children: Vec<FiberId> = (0..10)
+            .map(|_| fiber_tree.new_node(create_test_node("child")))
+            .collect();
+        for child_id in children.iter().rev() {
+            fiber_tree.insert_child(*child_id, root_id, None);
+        }
+        for child_id in &children[2..] {
+            fiber_tree.remove(*child_id);
+        }
+        fiber_tree.remove(children[0]);
+
+        fiber_tree.compact();
+
+        assert_eq!(fiber_tree.slots.len(), 3);
+        assert_eq!(fiber_tree.len(), 2);
+        assert!(fiber_tree.get(children[1]).is_some());
+        assert_eq!(
+            fiber_tree.new_node(create_test_node("new")),
+            FiberId {
+                index: children[0].index,
+                generation: children[0].generation + 1
+            }
+        );
+
+        let new_id = fiber_tree.new_node(create_test_node("new"));
+        assert_eq!(new_id.index, 3);
+        assert_ne!(new_id, children[2]);
+        assert!(fiber_tree.get(children[2]).is_none());
+    }
+
+    fn create_tree(fiber_tree: &mut FiberTree, size: usize) -> FiberId {
+        let root_id = fiber_tree.new_node(create_test_node("root"));
+        let mut parent_id = root_id;
+        for index in 1..size {
+            let id = fiber_tree.new_node(create_test_node("node"));
+            fiber_tree.insert_child(id, parent_id, None);
+            if index % 10 == 0 {
+                parent_id = id;
+            }
+        }
+
+        root_id
+    }
+
+    #[test]
+    fn reuse_slots_of_removed_trees() {
+        let mut fiber_tree = FiberTree::default();
+
+        for _ in 0..10 {
+            let root_id = create_tree(&mut fiber_tree, 10_000);
+            fiber_tree.remove(root_id);
+        }
+
+        assert_eq!(fiber_tree.len(), 0);
+        assert_eq!(fiber_tree.slots.len(), 10_000);
+
+        fiber_tree.compact();
+        assert!(fiber_tree.slots.is_empty());
+    }
+
+    #[test]
+    fn remove_siblings_in_any_order() {
+        let mut fiber_tree = FiberTree::default();
+
+        let parent_id = fiber_tree.new_node(create_test_node("parent"));
+        let children: Vec<FiberId> = (0..4)
+            .map(|_| fiber_tree.new_node(create_test_node("child")))
+            .collect();
+        for (index, child_id) in children.iter().enumerate() {
+            let after_id = index.checked_sub(1).map(|index| children[index]);
+            fiber_tree.insert_child(*child_id, parent_id, after_id);
+        }
+
+        fiber_tree.remove(children[2]);
+        let child_1 = fiber_tree.get(children[1]).unwrap();
+        assert_eq!(child_1.sibling, Some(children[3]));
+        assert_eq!(
+            fiber_tree.get(children[3]).unwrap().prev_sibling,
+            Some(children[1])
+        );
+
+        fiber_tree.remove(children[3]);
+        fiber_tree.remove(children[0]);
+        let child_1 = fiber_tree.get(children[1]).unwrap();
+        assert_eq!(fiber_tree.get(parent_id).unwrap().child, Some(children[1]));
+        assert_eq!(child_1.sibling, None);
+        assert_eq!(child_1.prev_sibling, None);
+    }
 }
@@ -2,35 +2,132 @@ use crate::fiber::FiberId;
 use std::fmt::Debug;
 
 mod use_effect;
+mod use_external_store;
+mod use_future;
+mod use_layout_effect;
+mod use_resource;
 mod use_state;
+mod use_stream;
+#[cfg(debug_assertions)]
+mod validation;
 
 use downcast_rs::{impl_downcast, Downcast};
 pub use use_effect::use_effect;
-pub use use_state::{use_state, State};
+pub(crate) use use_external_store::clear_pass_snapshots;
+pub use use_external_store::use_external_store;
+pub use use_future::{use_future, FutureState};
+pub use use_layout_effect::use_layout_effect;
+pub use use_resource::{use_resource, Resource};
+#[allow(deprecated)]
+pub use use_state::State;
+pub use use_state::{use_state, use_state_eq, use_state_eq_with, use_state_with, UseStateHandle};
+pub use use_stream::{use_stream, use_stream_fold};
 
 #[doc(hidden)]
 #[derive(Debug, Default)]
 pub struct HookContext {
     pub hooks: Vec<Box<dyn Hook>>,
     pub counter: usize,
+    pub(crate) suspended: bool,
+    pub(crate) suspense_boundary: Option<FiberId>,
+    #[cfg(debug_assertions)]
+    validation: validation::HookValidation,
 }
 
 impl HookContext {
-    fn get_mut_hook<T: Hook>(&mut self, hook_position: usize) -> &mut T {
+    pub(crate) fn start_render(&mut self, _component: &'static str) {
+        self.counter = 0;
+        self.suspended = false;
+        #[cfg(debug_assertions)]
+        self.validation.start_render(_component);
+    }
+
+    pub(crate) fn finish_render(&mut self) {
+        #[cfg(debug_assertions)]
+        self.validation.finish_render(self.counter);
+
+        for hook in self.hooks.iter_mut() {
+            hook.post_render();
+        }
+    }
+
+    /// Returns the hook at `hook_position`.
+    ///
+    /// In debug builds it also checks that the hooks are called in the same order on every render.
+    #[track_caller]
+    pub(crate) fn get_mut_hook<T: Hook>(&mut self, hook_position: usize) -> &mut T {
+        #[cfg(debug_assertions)]
+        self.validation.check_hook(
+            hook_position,
+            std::any::type_name::<T>(),
+            std::panic::Location::caller(),
+        );
+
         self.hooks
             .get_mut(hook_position)
             .and_then(|hook| hook.downcast_mut::<T>())
             .expect("Hook retrieval error")
     }
+
+    pub(crate) fn run_layout_effects(&mut self) {
+        for hook in self.hooks.iter_mut() {
+            hook.layout_effect();
+        }
+    }
+
+    pub(crate) fn run_effects(&mut self) {
+        for hook in self.hooks.iter_mut() {
+            hook.effect();
+        }
+    }
+
+    /// Returns `true` if a hook read a value during the render that changed since then
+    pub(crate) fn is_torn(&self) -> bool {
+        self.hooks.iter().any(|hook| hook.is_torn())
+    }
+
+    pub(crate) fn unmount(&mut self) {
+        for hook in self.hooks.iter_mut() {
+            hook.unmount();
+        }
+    }
+}
+
+#[cfg(test)]
+impl HookContext {
+    /// Renders again a component whose only hook is built by `builder`.
+    ///
+    /// The hook is always built from this line, like a component that calls its hooks
+    /// from the same lines on every render.
+    pub(crate) fn render_hook<T>(&mut self, fiber_id: FiberId, builder: impl HookBuilder<T>) -> T {
+        self.counter = 0;
+        builder.build(&mut (fiber_id, self))
+    }
 }
 
 #[doc(hidden)]
 pub trait HookBuilder<T> {
+    #[track_caller]
     fn build(self, context: &mut (FiberId, &mut HookContext)) -> T;
 }
 
 #[doc(hidden)]
 pub trait Hook: Downcast + Debug {
-    fn post_render(&mut self);
+    fn post_render(&mut self) {}
+
+    /// Called synchronously after the DOM has been mutated, before the browser paints
+    fn layout_effect(&mut self) {}
+
+    /// Called once the layout effects of the committed tree have been executed
+    fn effect(&mut self) {}
+
+    /// Called when the component that owns the hook is removed from the tree
+    fn unmount(&mut self) {}
+
+    /// Returns `true` if the value read during the render is out of date,
+    /// so the component is rendered again before the render is committed
+    fn is_torn(&self) -> bool {
+        false
+    }
 }
 impl_downcast!(Hook);
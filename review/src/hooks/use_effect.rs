@@ -13,6 +13,9 @@ pub struct UseEffectBuilder<E, D: PartialEq> {
 
 /// The Effect Hook lets you perform side effects in components
 ///
+/// The effect runs after the component's changes have been committed to the DOM,
+/// once every [use_layout_effect](crate::use_layout_effect) of the committed tree has been executed.
+///
 /// # Examples
 ///
 /// Without cleanup function and without dependencies
@@ -78,10 +81,66 @@ pub fn use_effect<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + Pa
     }
 }
 
-struct EffectHook<E: Fn() -> Option<C>, C: Fn(), D: PartialEq> {
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(super) enum EffectPhase {
+    Layout,
+    Passive,
+}
+
+pub(super) struct EffectHook<E: Fn() -> Option<C>, C: Fn(), D: PartialEq> {
+    pub effect: E,
+    pub current_dependencies: Option<D>,
+    pub last_dependencies: Option<D>,
+    pub phase: EffectPhase,
+}
+
+impl<E: Fn() -> Option<C>, C: Fn(), D: PartialEq> EffectHook<E, C, D> {
+    fn run(&mut self) {
+        if self.current_dependencies.is_none()
+            || self.current_dependencies != self.last_dependencies
+        {
+            let cleanup = (self.effect)();
+            if let Some(cleanup) = cleanup {
+                cleanup();
+            }
+            self.last_dependencies = self.current_dependencies.take();
+        }
+    }
+}
+
+#[track_caller]
+pub(super) fn build_effect_hook<
+    E: Fn() -> Option<C> + 'static,
+    C: Fn() + 'static,
+    D: Any + PartialEq,
+>(
     effect: E,
-    current_dependencies: Option<D>,
-    last_dependencies: Option<D>,
+    dependencies: Option<D>,
+    phase: EffectPhase,
+    hook_context: &mut HookContext,
+) {
+    let hook_position = hook_context.counter;
+    hook_context.counter += 1;
+    if hook_position >= hook_context.hooks.len() {
+        let initial_value = EffectHook {
+            effect,
+            current_dependencies: dependencies,
+            last_dependencies: None,
+            phase,
+        };
+        hook_context.hooks.push(Box::new(initial_value));
+        #[cfg(debug_assertions)]
+        hook_context.validation.record(
+            hook_position,
+            std::any::type_name::<EffectHook<E, C, D>>(),
+            std::panic::Location::caller(),
+        );
+    } else {
+        let hook: &mut EffectHook<E, C, D> = hook_context.get_mut_hook(hook_position);
+
+        hook.effect = effect;
+        hook.current_dependencies = dependencies;
+    }
 }
 
 impl<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq> Debug
@@ -95,15 +154,15 @@ impl<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq> Debu
 impl<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq> Hook
     for EffectHook<E, C, D>
 {
-    fn post_render(&mut self) {
-        if self.current_dependencies.is_none()
-            || self.current_dependencies != self.last_dependencies
-        {
-            let cleanup = (self.effect)();
-            if let Some(cleanup) = cleanup {
-                cleanup();
-            }
-            self.last_dependencies = self.current_dependencies.take();
+    fn layout_effect(&mut self) {
+        if self.phase == EffectPhase::Layout {
+            self.run();
+        }
+    }
+
+    fn effect(&mut self) {
+        if self.phase == EffectPhase::Passive {
+            self.run();
         }
     }
 }
@@ -112,21 +171,12 @@ impl<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq> Hook
     for UseEffectBuilder<E, D>
 {
     fn build(self, (_, hook_context): &mut (FiberId, &mut HookContext)) {
-        let hook_position = hook_context.counter;
-        hook_context.counter += 1;
-        if hook_position >= hook_context.hooks.len() {
-            let initial_value = EffectHook {
-                effect: self.effect,
-                current_dependencies: self.dependencies,
-                last_dependencies: None,
-            };
-            hook_context.hooks.push(Box::new(initial_value));
-        } else {
-            let hook: &mut EffectHook<E, C, D> = hook_context.get_mut_hook(hook_position);
-
-            hook.effect = self.effect;
-            hook.current_dependencies = self.dependencies;
-        }
+        build_effect_hook(
+            self.effect,
+            self.dependencies,
+            EffectPhase::Passive,
+            hook_context,
+        );
     }
 }
 
@@ -142,7 +192,7 @@ mod tests {
 
         let counter = Rc::new(RefCell::new(0));
 
-        let hook_context = &mut (0, &mut context);
+        let hook_context = &mut (0.into(), &mut context);
 
         use_effect(
             {
@@ -158,9 +208,7 @@ mod tests {
         )
         .build(hook_context);
 
-        for h in context.hooks.iter_mut() {
-            h.post_render();
-        }
+        context.run_effects();
         assert_eq!(*counter.borrow(), 1);
     }
 
@@ -172,7 +220,7 @@ mod tests {
 
         let counter = Rc::new(RefCell::new(0));
 
-        let effect = |hook_context: &mut (usize, &mut HookContext), dep: u32| {
+        let effect = |hook_context: &mut (FiberId, &mut HookContext), dep: u32| {
             use_effect(
                 {
                     let counter = counter.clone();
@@ -189,24 +237,20 @@ mod tests {
         };
 
         {
-            let hook_context = &mut (0, &mut context);
+            let hook_context = &mut (0.into(), &mut context);
             (effect)(hook_context, dep);
 
-            for h in context.hooks.iter_mut() {
-                h.post_render();
-            }
+            context.run_effects();
             assert_eq!(*counter.borrow(), 1);
         }
 
         context.counter = 0;
 
         {
-            let hook_context = &mut (0, &mut context);
+            let hook_context = &mut (0.into(), &mut context);
             (effect)(hook_context, dep);
 
-            for h in context.hooks.iter_mut() {
-                h.post_render();
-            }
+            context.run_effects();
             assert_eq!(*counter.borrow(), 1);
         }
 
@@ -214,12 +258,10 @@ mod tests {
         context.counter = 0;
 
         {
-            let hook_context = &mut (0, &mut context);
+            let hook_context = &mut (0.into(), &mut context);
             (effect)(hook_context, dep);
 
-            for h in context.hooks.iter_mut() {
-                h.post_render();
-            }
+            context.run_effects();
             assert_eq!(*counter.borrow(), 2);
         }
     }
@@ -231,7 +273,7 @@ mod tests {
         let counter = Rc::new(RefCell::new(0));
         let clean = Rc::new(RefCell::new(1));
 
-        let hook_context = &mut (0, &mut context);
+        let hook_context = &mut (0.into(), &mut context);
 
         use_effect(
             {
@@ -252,9 +294,7 @@ mod tests {
         )
         .build(hook_context);
 
-        for h in context.hooks.iter_mut() {
-            h.post_render();
-        }
+        context.run_effects();
         assert_eq!(*counter.borrow(), 1);
         assert_eq!(*clean.borrow(), 0);
     }
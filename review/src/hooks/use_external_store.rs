@@ -0,0 +1,314 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::Hook;
+use crate::HookBuilder;
+use crate::HookContext;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+type Subscribe = Box<dyn FnOnce(Rc<dyn Fn()>) -> Box<dyn FnOnce()>>;
+
+thread_local! {
+    /// The snapshots read by the render pass in progress, by the type of their `get_snapshot`
+    static PASS_SNAPSHOTS: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Forgets the snapshots of the render pass, so the next one reads the sources again
+pub(crate) fn clear_pass_snapshots() {
+    PASS_SNAPSHOTS.with(|snapshots| snapshots.borrow_mut().clear());
+}
+
+/// Reads the snapshot once per render pass for every store.
+///
+/// A `get_snapshot` that captures nothing is identified by its type, so the components of the same
+/// pass using it share the first snapshot read. The other ones are read on every call.
+fn pass_snapshot<G, T>(get_snapshot: &G) -> T
+where
+    G: Fn() -> T + 'static,
+    T: Clone + 'static,
+{
+    if std::mem::size_of::<G>() != 0 {
+        return get_snapshot();
+    }
+
+    let cached = PASS_SNAPSHOTS.with(|snapshots| {
+        snapshots
+            .borrow()
+            .get(&TypeId::of::<G>())
+            .and_then(|snapshot| snapshot.downcast_ref::<T>())
+            .cloned()
+    });
+
+    cached.unwrap_or_else(|| {
+        let snapshot = get_snapshot();
+        PASS_SNAPSHOTS.with(|snapshots| {
+            snapshots
+                .borrow_mut()
+                .insert(TypeId::of::<G>(), Box::new(snapshot.clone()))
+        });
+
+        snapshot
+    })
+}
+
+pub struct UseExternalStoreBuilder<S, G> {
+    subscribe: S,
+    get_snapshot: G,
+}
+
+/// This hook reads a snapshot of an external mutable source and re-renders the component when it changes.
+///
+/// `subscribe` receives a function to call every time the source changes and returns the
+/// function that removes the subscription.
+/// The subscription is created after the first render is committed and removed when the
+/// component is removed from the tree.
+///
+/// `get_snapshot` returns the current value of the source.
+/// The component is rendered again only when the snapshot changes.
+/// When `get_snapshot` is a function, or a closure that captures nothing, it's called once per
+/// render pass, and every component using it gets the same snapshot.
+/// The snapshot is checked again before the render is committed: if the source changed while the
+/// tree was rendered, the component is rendered again before the commit, so the committed tree
+/// never shows different snapshots of the same source.
+///
+/// > Note
+/// >
+/// > The snapshot must implement `PartialEq` and `Clone`
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_external_store, ElementBuilder};
+/// # use review::Tag::P;
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// thread_local! {
+///     static LISTENERS: RefCell<Vec<Rc<dyn Fn()>>> = RefCell::new(Vec::new());
+///     static ONLINE: RefCell<bool> = RefCell::new(true);
+/// }
+///
+/// #[component(Status)]
+/// pub fn status() -> VNode {
+///     let online = use_external_store(
+///         |on_change| {
+///             LISTENERS.with(|listeners| listeners.borrow_mut().push(on_change.clone()));
+///             move || {
+///                 LISTENERS.with(|listeners| {
+///                     listeners
+///                         .borrow_mut()
+///                         .retain(|listener| !Rc::ptr_eq(listener, &on_change))
+///                 })
+///             }
+///         },
+///         || ONLINE.with(|online| *online.borrow()),
+///     );
+///
+///     P.with_child(if online { "Online" } else { "Offline" }).into()
+/// }
+/// ```
+pub fn use_external_store<S, U, G, T>(
+    subscribe: S,
+    get_snapshot: G,
+) -> UseExternalStoreBuilder<S, G>
+where
+    S: FnOnce(Rc<dyn Fn()>) -> U + 'static,
+    U: FnOnce() + 'static,
+    G: Fn() -> T + 'static,
+    T: PartialEq + Clone + 'static,
+{
+    UseExternalStoreBuilder {
+        subscribe,
+        get_snapshot,
+    }
+}
+
+struct ExternalStoreHook<T> {
+    snapshot: Rc<RefCell<T>>,
+    get_snapshot: Rc<RefCell<Rc<dyn Fn() -> T>>>,
+    subscribe: Option<Subscribe>,
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+    fiber_id: FiberId,
+}
+
+impl<T> Debug for ExternalStoreHook<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("ExternalStoreHook")
+            .field("fiber_id", &self.fiber_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: PartialEq + 'static> ExternalStoreHook<T> {
+    fn on_change(&self) -> Rc<dyn Fn()> {
+        let snapshot = self.snapshot.clone();
+        let get_snapshot = self.get_snapshot.clone();
+        let fiber_id = self.fiber_id;
+
+        Rc::new(move || {
+            let get_snapshot = get_snapshot.borrow().clone();
+            if get_snapshot() != *snapshot.borrow() {
+                schedule_update(fiber_id);
+            }
+        })
+    }
+}
+
+impl<T: PartialEq + 'static> Hook for ExternalStoreHook<T> {
+    fn effect(&mut self) {
+        if let Some(subscribe) = self.subscribe.take() {
+            let on_change = self.on_change();
+            self.unsubscribe = Some(subscribe(on_change.clone()));
+
+            // The source changed before the subscription was created
+            on_change();
+        }
+    }
+
+    fn unmount(&mut self) {
+        self.subscribe = None;
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+
+    fn is_torn(&self) -> bool {
+        let get_snapshot = self.get_snapshot.borrow().clone();
+
+        get_snapshot() != *self.snapshot.borrow()
+    }
+}
+
+impl<S, U, G, T> HookBuilder<T> for UseExternalStoreBuilder<S, G>
+where
+    S: FnOnce(Rc<dyn Fn()>) -> U + 'static,
+    U: FnOnce() + 'static,
+    G: Fn() -> T + 'static,
+    T: PartialEq + Clone + 'static,
+{
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> T {
+        let snapshot = pass_snapshot(&self.get_snapshot);
+        let get_snapshot: Rc<dyn Fn() -> T> = Rc::new(self.get_snapshot);
+
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let subscribe = self.subscribe;
+            let initial_value: ExternalStoreHook<T> = ExternalStoreHook {
+                snapshot: Rc::new(RefCell::new(snapshot.clone())),
+                get_snapshot: Rc::new(RefCell::new(get_snapshot.clone())),
+                subscribe: Some(Box::new(move |on_change| {
+                    Box::new(subscribe(on_change)) as Box<dyn FnOnce()>
+                })),
+                unsubscribe: None,
+                fiber_id: *fiber_id,
+            };
+            hook_context.hooks.push(Box::new(initial_value));
+        }
+
+        let hook: &mut ExternalStoreHook<T> = hook_context.get_mut_hook(hook_position);
+        hook.get_snapshot.replace(get_snapshot);
+        hook.snapshot.replace(snapshot.clone());
+
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::take_scheduled_updates;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Source {
+        value: Cell<u32>,
+        listeners: RefCell<Vec<Rc<dyn Fn()>>>,
+    }
+
+    impl Source {
+        fn set(&self, value: u32) {
+            self.value.set(value);
+            let listeners = self.listeners.borrow().clone();
+            for listener in listeners {
+                listener();
+            }
+        }
+    }
+
+    fn build(source: &Rc<Source>, context: &mut HookContext) -> u32 {
+        let (subscribe_source, snapshot_source) = (source.clone(), source.clone());
+        context.counter = 0;
+        use_external_store(
+            move |on_change| {
+                subscribe_source.listeners.borrow_mut().push(on_change);
+                move || subscribe_source.listeners.borrow_mut().clear()
+            },
+            move || snapshot_source.value.get(),
+        )
+        .build(&mut (5.into(), context))
+    }
+
+    #[test]
+    fn subscribe_after_commit() {
+        let source = Rc::new(Source::default());
+        let mut context = HookContext::default();
+
+        assert_eq!(build(&source, &mut context), 0);
+        assert!(source.listeners.borrow().is_empty());
+
+        context.run_layout_effects();
+        context.run_effects();
+        assert_eq!(source.listeners.borrow().len(), 1);
+
+        take_scheduled_updates();
+        source.set(0);
+        assert!(take_scheduled_updates().is_empty());
+        source.set(1);
+        assert_eq!(take_scheduled_updates(), vec![FiberId::from(5)]);
+        assert_eq!(build(&source, &mut context), 1);
+
+        context.unmount();
+        assert!(source.listeners.borrow().is_empty());
+    }
+
+    #[test]
+    fn change_during_render_is_torn() {
+        let source = Rc::new(Source::default());
+        let mut context = HookContext::default();
+
+        build(&source, &mut context);
+        assert!(!context.is_torn());
+
+        source.value.set(2);
+        assert!(context.is_torn());
+        assert_eq!(build(&source, &mut context), 2);
+        assert!(!context.is_torn());
+    }
+
+    thread_local! {
+        static SHARED_SOURCE: Cell<u32> = Cell::new(0);
+    }
+
+    fn shared_snapshot() -> u32 {
+        SHARED_SOURCE.with(Cell::get)
+    }
+
+    #[test]
+    fn one_snapshot_per_pass() {
+        let mut first = HookContext::default();
+        let mut second = HookContext::default();
+        let store = || use_external_store(|_| || (), shared_snapshot);
+
+        assert_eq!(first.render_hook(1.into(), store()), 0);
+        SHARED_SOURCE.with(|source| source.set(1));
+        assert_eq!(second.render_hook(2.into(), store()), 0);
+        assert!(first.is_torn() && second.is_torn());
+
+        clear_pass_snapshots();
+        assert_eq!(second.render_hook(2.into(), store()), 1);
+        assert!(!second.is_torn());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::spawn_local;
+use crate::Hook;
+use crate::HookBuilder;
+use crate::HookContext;
+use futures::future::{abortable, AbortHandle};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::future::Future;
+use std::rc::Rc;
+
+/// The state of the future handled by a [use_future] hook
+#[derive(Debug, PartialEq, Clone)]
+pub enum FutureState<T, E> {
+    /// The future is still running
+    Loading,
+    /// The future is completed successfully
+    Ready(T),
+    /// The future is completed with an error
+    Failed(E),
+}
+
+pub struct UseFutureBuilder<F, D> {
+    factory: F,
+    dependencies: D,
+}
+
+/// This hook runs the future returned by `factory` and re-renders the component when it completes.
+///
+/// Returns the current [FutureState] of the future.
+///
+/// The future is started during the first render and every time the `dependencies` change.
+/// When this happens, or when the component is removed from the tree, the previous future
+/// is cancelled and its result is never used.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_future, FutureState, ElementBuilder};
+/// # use review::Tag::P;
+/// # async fn fetch_user(id: u32) -> Result<String, String> { Ok(format!("user {}", id)) }
+/// #[component(Example)]
+/// pub fn example(user_id: &u32) -> VNode {
+///     let user = use_future(
+///         {
+///             let user_id = *user_id;
+///             move || fetch_user(user_id)
+///         },
+///         *user_id,
+///     );
+///
+///     match &*user {
+///         FutureState::Loading => P.with_child("Loading...").into(),
+///         FutureState::Ready(user) => P.with_child(user).into(),
+///         FutureState::Failed(error) => P.with_child(format!("Error: {}", error)).into(),
+///     }
+/// }
+/// ```
+pub fn use_future<F, Fut, T, E, D>(factory: F, dependencies: D) -> UseFutureBuilder<F, D>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+    D: Any + PartialEq,
+{
+    UseFutureBuilder {
+        factory,
+        dependencies,
+    }
+}
+
+struct FutureHook<T, E, D> {
+    state: Rc<RefCell<Rc<FutureState<T, E>>>>,
+    dependencies: Option<D>,
+    abort_handle: Option<AbortHandle>,
+}
+
+impl<T, E, D> Debug for FutureHook<T, E, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("FutureHook").finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static, E: 'static, D: Any + PartialEq> Hook for FutureHook<T, E, D> {
+    fn unmount(&mut self) {
+        if let Some(abort_handle) = self.abort_handle.take() {
+            abort_handle.abort();
+        }
+    }
+}
+
+impl<F, Fut, T, E, D> HookBuilder<Rc<FutureState<T, E>>> for UseFutureBuilder<F, D>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+    D: Any + PartialEq,
+{
+    fn build(
+        self,
+        (fiber_id, hook_context): &mut (FiberId, &mut HookContext),
+    ) -> Rc<FutureState<T, E>> {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let initial_value: FutureHook<T, E, D> = FutureHook {
+                state: Rc::new(RefCell::new(Rc::new(FutureState::Loading))),
+                dependencies: None,
+                abort_handle: None,
+            };
+            hook_context.hooks.push(Box::new(initial_value));
+        }
+
+        let hook: &mut FutureHook<T, E, D> = hook_context.get_mut_hook(hook_position);
+        if hook.dependencies.as_ref() != Some(&self.dependencies) {
+            hook.unmount();
+            hook.dependencies = Some(self.dependencies);
+            hook.state.replace(Rc::new(FutureState::Loading));
+
+            let (future, abort_handle) = abortable((self.factory)());
+            let state = hook.state.clone();
+            let fiber_id = *fiber_id;
+            spawn_local(async move {
+                if let Ok(result) = future.await {
+                    state.replace(Rc::new(match result {
+                        Ok(value) => FutureState::Ready(value),
+                        Err(error) => FutureState::Failed(error),
+                    }));
+                    schedule_update(fiber_id);
+                }
+            });
+            hook.abort_handle = Some(abort_handle);
+        }
+
+        let state = hook.state.borrow().clone();
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_until_stalled;
+    use futures::channel::oneshot;
+
+    #[test]
+    fn future_ready() {
+        let mut context = HookContext::default();
+
+        let state = context.render_hook(0.into(), use_future(|| async { Ok::<_, ()>(7) }, ()));
+        assert_eq!(*state, FutureState::Loading);
+
+        run_until_stalled();
+
+        let state = context.render_hook(0.into(), use_future(|| async { Ok::<_, ()>(7) }, ()));
+        assert_eq!(*state, FutureState::Ready(7));
+    }
+
+    #[test]
+    fn future_failed() {
+        let mut context = HookContext::default();
+
+        let future = || async { Err::<(), _>("error") };
+        context.render_hook(0.into(), use_future(future, ()));
+
+        run_until_stalled();
+
+        let state = context.render_hook(0.into(), use_future(future, ()));
+        assert_eq!(*state, FutureState::Failed("error"));
+    }
+
+    #[test]
+    fn stale_future_is_ignored() {
+        let mut context = HookContext::default();
+
+        let (old_sender, old_receiver) = oneshot::channel::<u32>();
+        let (new_sender, new_receiver) = oneshot::channel::<u32>();
+
+        context.render_hook(0.into(), use_future(move || old_receiver, 1));
+
+        let state = context.render_hook(0.into(), use_future(move || new_receiver, 2));
+        assert_eq!(*state, FutureState::Loading);
+
+        new_sender.send(2).unwrap();
+        run_until_stalled();
+        assert!(old_sender.send(1).is_err());
+
+        let state = context.render_hook(
+            0.into(),
+            use_future(|| async { Ok::<u32, oneshot::Canceled>(3) }, 2),
+        );
+        assert_eq!(*state, FutureState::Ready(2));
+    }
+
+    #[test]
+    fn future_cancelled_on_unmount() {
+        let mut context = HookContext::default();
+
+        let (sender, receiver) = oneshot::channel::<u32>();
+
+        context.render_hook(0.into(), use_future(move || receiver, ()));
+
+        context.unmount();
+        run_until_stalled();
+
+        assert!(sender.send(1).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use super::use_effect::{build_effect_hook, EffectPhase};
+use crate::fiber::FiberId;
+use crate::HookBuilder;
+use crate::HookContext;
+use std::any::Any;
+
+pub struct UseLayoutEffectBuilder<E, D: PartialEq> {
+    effect: E,
+    dependencies: Option<D>,
+}
+
+/// The Layout Effect Hook is identical to [use_effect](crate::use_effect), but it fires
+/// synchronously right after the DOM has been mutated and before the browser paints
+///
+/// Use it to read the layout from the DOM (e.g. `getBoundingClientRect`) and synchronously
+/// re-render, without any visible flicker.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, log, component, use_layout_effect};
+/// # use review::Tag::Div;
+/// #[component(Example)]
+/// pub fn example() -> VNode {
+///     use_layout_effect(
+///         || {
+///             log::info!("the DOM is updated but not painted yet");
+///             Some(|| log::info!("clean"))
+///         },
+///         Some(()) // run only one time because () never change
+///     );
+///
+///     Div.into()
+/// }
+/// ```
+pub fn use_layout_effect<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq>(
+    effect: E,
+    dependencies: Option<D>,
+) -> UseLayoutEffectBuilder<E, D> {
+    UseLayoutEffectBuilder {
+        effect,
+        dependencies,
+    }
+}
+
+impl<E: Fn() -> Option<C> + 'static, C: Fn() + 'static, D: Any + PartialEq> HookBuilder<()>
+    for UseLayoutEffectBuilder<E, D>
+{
+    fn build(self, (_, hook_context): &mut (FiberId, &mut HookContext)) {
+        build_effect_hook(
+            self.effect,
+            self.dependencies,
+            EffectPhase::Layout,
+            hook_context,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::use_effect;
+
+    #[test]
+    fn layout_effect_call() {
+        let mut context = HookContext::default();
+
+        let counter = Rc::new(RefCell::new(0));
+
+        let hook_context = &mut (0.into(), &mut context);
+
+        use_layout_effect(
+            {
+                let counter = counter.clone();
+                move || {
+                    let mut c_mut = counter.borrow_mut();
+                    *c_mut += 1;
+
+                    None::<fn()>
+                }
+            },
+            None::<()>,
+        )
+        .build(hook_context);
+
+        context.run_effects();
+        assert_eq!(*counter.borrow(), 0);
+
+        context.run_layout_effects();
+        assert_eq!(*counter.borrow(), 1);
+    }
+
+    #[test]
+    fn layout_effect_runs_before_effect() {
+        let mut context = HookContext::default();
+
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let effects = |hook_context: &mut (FiberId, &mut HookContext)| {
+            use_effect(
+                {
+                    let calls = calls.clone();
+                    move || {
+                        calls.borrow_mut().push("effect");
+
+                        None::<fn()>
+                    }
+                },
+                Some(()),
+            )
+            .build(hook_context);
+            use_layout_effect(
+                {
+                    let calls = calls.clone();
+                    move || {
+                        calls.borrow_mut().push("layout");
+
+                        None::<fn()>
+                    }
+                },
+                Some(()),
+            )
+            .build(hook_context);
+        };
+
+        {
+            let hook_context = &mut (0.into(), &mut context);
+            (effects)(hook_context);
+
+            context.run_layout_effects();
+            context.run_effects();
+            assert_eq!(*calls.borrow(), vec!["layout", "effect"]);
+        }
+
+        context.counter = 0;
+
+        {
+            let hook_context = &mut (0.into(), &mut context);
+            (effects)(hook_context);
+
+            context.run_layout_effects();
+            context.run_effects();
+            assert_eq!(*calls.borrow(), vec!["layout", "effect"]);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::fiber::FiberId;
+use crate::use_future;
+use crate::FutureState;
+use crate::HookBuilder;
+use crate::HookContext;
+use std::any::Any;
+use std::convert::Infallible;
+use std::future::Future;
+use std::rc::Rc;
+
+/// The value returned from a [use_resource] hook
+#[derive(Debug, PartialEq)]
+pub enum Resource<T> {
+    /// The resource is still loading and the component is suspended
+    Pending,
+    /// The resource is loaded
+    Ready(Rc<T>),
+}
+
+pub struct UseResourceBuilder<F, D> {
+    factory: F,
+    dependencies: D,
+}
+
+/// This hook loads a resource with the future returned by `factory` and suspends the component
+/// until the future is completed.
+///
+/// While the future is running the hook returns [Resource::Pending] and the nearest
+/// [Suspense](crate::Suspense) boundary renders its fallback instead of its children.
+/// When the future completes the boundary renders its children again.
+///
+/// Like [use_future], the future is started again every time the `dependencies` change.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_resource, Resource, ElementBuilder};
+/// # use review::Tag::P;
+/// # async fn fetch_user(id: u32) -> String { format!("user {}", id) }
+/// #[component(User)]
+/// pub fn user(user_id: &u32) -> VNode {
+///     let user = use_resource(
+///         {
+///             let user_id = *user_id;
+///             move || fetch_user(user_id)
+///         },
+///         *user_id,
+///     );
+///
+///     match user {
+///         Resource::Ready(user) => P.with_child(&*user).into(),
+///         Resource::Pending => "".into(),
+///     }
+/// }
+/// ```
+pub fn use_resource<F, Fut, T, D>(factory: F, dependencies: D) -> UseResourceBuilder<F, D>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T> + 'static,
+    T: 'static,
+    D: Any + PartialEq,
+{
+    UseResourceBuilder {
+        factory,
+        dependencies,
+    }
+}
+
+impl<F, Fut, T, D> HookBuilder<Resource<T>> for UseResourceBuilder<F, D>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T> + 'static,
+    T: 'static,
+    D: Any + PartialEq,
+{
+    fn build(self, context: &mut (FiberId, &mut HookContext)) -> Resource<T> {
+        let factory = self.factory;
+        let state = use_future(
+            move || {
+                let future = factory();
+                async move { Ok::<_, Infallible>(Rc::new(future.await)) }
+            },
+            self.dependencies,
+        )
+        .build(context);
+
+        match &*state {
+            FutureState::Ready(value) => Resource::Ready(value.clone()),
+            _ => {
+                context.1.suspended = true;
+                Resource::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_until_stalled;
+
+    #[test]
+    fn resource_suspends_until_ready() {
+        let mut context = HookContext::default();
+
+        let resource = context.render_hook(0.into(), use_resource(|| async { 7 }, ()));
+        assert_eq!(resource, Resource::Pending);
+        assert!(context.suspended);
+
+        run_until_stalled();
+
+        context.suspended = false;
+        let resource = context.render_hook(0.into(), use_resource(|| async { 7 }, ()));
+        assert_eq!(resource, Resource::Ready(Rc::new(7)));
+        assert!(!context.suspended);
+    }
+}
@@ -1,23 +1,115 @@
-use crate::app::APP;
 use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
 use crate::Hook;
 use crate::HookBuilder;
 use crate::HookContext;
 use std::any::Any;
 use std::cell::RefCell;
 use std::fmt::Debug;
+use std::ops::Deref;
 use std::rc::Rc;
 
-/// The type returned from a [use_state] hook
+/// The tuple returned from [use_state] before [UseStateHandle].
+///
+/// A handle can be converted into it while migrating:
+/// `let (counter, set_counter): State<u32> = use_state(0).into();`
+#[deprecated(
+    note = "use_state returns a UseStateHandle, that can be converted into a State with `into()`"
+)]
 pub type State<T> = (Rc<T>, Rc<dyn Fn(T)>);
 
-pub struct UseStateBuilder<T> {
-    initial_value: T,
+/// The handle returned from a [use_state] hook
+///
+/// It dereferences to the value stored during the render and exposes the functions
+/// to change it. Every change schedules a new render of the component.
+pub struct UseStateHandle<T> {
+    value: Rc<T>,
+    hook: StateHook<T>,
+    fiber_id: FiberId,
+}
+
+impl<T> UseStateHandle<T> {
+    /// Replaces the stored value with `new_value`
+    pub fn set(&self, new_value: T) {
+        self.update(move |_| new_value);
+    }
+
+    /// Computes the new stored value from the most recent one.
+    ///
+    /// Unlike `set(*state + 1)`, the update is based on the latest value even when
+    /// the handle has been captured during an older render.
+    pub fn update<F: FnOnce(&T) -> T>(&self, f: F) {
+        let changed = {
+            let mut hook = self.hook.0.borrow_mut();
+            let new_value = f(&hook.value);
+            let changed = hook.eq.map_or(true, |eq| !eq(&hook.value, &new_value));
+            if changed {
+                hook.value = Rc::new(new_value);
+            }
+
+            changed
+        };
+
+        if changed {
+            schedule_update(self.fiber_id);
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl<T: 'static> From<UseStateHandle<T>> for State<T> {
+    fn from(handle: UseStateHandle<T>) -> Self {
+        let value = handle.value.clone();
+
+        (value, Rc::new(move |new_value| handle.set(new_value)))
+    }
+}
+
+impl<T> Deref for UseStateHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Clone for UseStateHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hook: self.hook.clone(),
+            fiber_id: self.fiber_id,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for UseStateHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Debug> Debug for UseStateHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseStateHandle")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+pub struct UseStateBuilder<T, I: FnOnce() -> T> {
+    init: I,
+    eq: Option<fn(&T, &T) -> bool>,
 }
 
 /// This hook is used to manage the state in a component.
 ///
-/// Returns a [State<T>]
+/// Returns a [UseStateHandle<T>]
+///
+/// Every update triggers a new render, even when the new value is equal to the stored one.
+/// Use [use_state_eq] to skip them for types that implement [PartialEq]: `use_state` can't
+/// detect the trait by itself without specialization, and requiring it would exclude states
+/// like closures or handles that can't be compared.
 ///
 /// # Example
 /// ```rust
@@ -26,60 +118,104 @@ pub struct UseStateBuilder<T> {
 /// # use review::EventType::OnClick;
 /// #[component(Example)]
 /// pub fn example() -> VNode {
-///     let (counter, set_counter) = use_state(0);
-///     let on_click = {
-///         let counter = counter.clone();
-///         let set_counter = set_counter.clone();
-///         move || {
-///             set_counter(*counter + 1);
-///         }
-///     };
+///     let counter = use_state(0);
 ///     Div.with_children(children!(
 ///         Button
-///             .with_event(OnClick, callback!(move || on_click()))
+///             .with_event(OnClick, callback!([counter] move || counter.update(|prev| prev + 1)))
 ///             .with_child("Increment value"),
-///         P.with_child(format!("Current value {}", counter))
+///         P.with_child(format!("Current value {}", *counter))
 ///     )).into()
 /// }
 /// ```
-pub fn use_state<T: Any + Debug>(initial_value: T) -> UseStateBuilder<T> {
-    UseStateBuilder { initial_value }
+pub fn use_state<T: Any + Debug>(initial_value: T) -> UseStateBuilder<T, impl FnOnce() -> T> {
+    use_state_with(move || initial_value)
 }
 
-type StateHook<T> = Rc<RefCell<Rc<T>>>;
+/// Like [use_state] but the initial value is computed by `init`.
+///
+/// `init` is called only during the first render of the component.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, use_state_with, component, ElementBuilder};
+/// # use review::Tag::P;
+/// #[component(Example)]
+/// pub fn example() -> VNode {
+///     let items = use_state_with(|| (0..1000).collect::<Vec<u32>>());
+///
+///     P.with_child(format!("{} items", items.len())).into()
+/// }
+/// ```
+pub fn use_state_with<T: Any + Debug, I: FnOnce() -> T>(init: I) -> UseStateBuilder<T, I> {
+    UseStateBuilder { init, eq: None }
+}
 
-impl<T: Any + Debug> Hook for StateHook<T> {
-    fn post_render(&mut self) {}
+/// Like [use_state] but an update that doesn't change the stored value,
+/// according to [PartialEq], doesn't trigger a new render.
+pub fn use_state_eq<T: Any + Debug + PartialEq>(
+    initial_value: T,
+) -> UseStateBuilder<T, impl FnOnce() -> T> {
+    use_state_eq_with(move || initial_value)
 }
 
-impl<T: Any + Debug> HookBuilder<State<T>> for UseStateBuilder<T> {
-    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> State<T> {
-        let (fiber_target_id, hook): (FiberId, &StateHook<T>) = {
-            let hook_position = hook_context.counter;
-            hook_context.counter += 1;
-            if hook_position >= hook_context.hooks.len() {
-                let initial_value = StateHook::new(RefCell::new(Rc::new(self.initial_value)));
-                hook_context.hooks.push(Box::new(initial_value));
-            }
+/// Like [use_state_with] but an update that doesn't change the stored value,
+/// according to [PartialEq], doesn't trigger a new render.
+pub fn use_state_eq_with<T: Any + Debug + PartialEq, I: FnOnce() -> T>(
+    init: I,
+) -> UseStateBuilder<T, I> {
+    UseStateBuilder {
+        init,
+        eq: Some(T::eq),
+    }
+}
 
-            (*fiber_id, hook_context.get_mut_hook(hook_position))
-        };
-        let update_hook = hook.clone();
-        let updater = move |new_value: T| {
-            {
-                update_hook.replace(Rc::new(new_value));
-            }
-            APP.with(|app| {
-                if let Ok(mut app) = app.try_borrow_mut() {
-                    if let Some(app) = &mut *app {
-                        app.wip_root = Some(fiber_target_id);
-                        app.next_unit_of_work = Some(fiber_target_id);
-                    }
-                }
-            });
-        };
-        let hook = hook.borrow();
-        (hook.clone(), Rc::new(updater))
+struct StateCell<T> {
+    value: Rc<T>,
+    eq: Option<fn(&T, &T) -> bool>,
+}
+
+impl<T: Debug> Debug for StateCell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateCell")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct StateHook<T>(Rc<RefCell<StateCell<T>>>);
+
+impl<T> Clone for StateHook<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Any + Debug> Hook for StateHook<T> {}
+
+impl<T: Any + Debug, I: FnOnce() -> T> HookBuilder<UseStateHandle<T>> for UseStateBuilder<T, I> {
+    fn build(
+        self,
+        (fiber_id, hook_context): &mut (FiberId, &mut HookContext),
+    ) -> UseStateHandle<T> {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let initial_value = StateHook(Rc::new(RefCell::new(StateCell {
+                value: Rc::new((self.init)()),
+                eq: self.eq,
+            })));
+            hook_context.hooks.push(Box::new(initial_value));
+        }
+
+        let hook: &StateHook<T> = hook_context.get_mut_hook(hook_position);
+        let value = hook.0.borrow().value.clone();
+
+        UseStateHandle {
+            value,
+            hook: hook.clone(),
+            fiber_id: *fiber_id,
+        }
     }
 }
 
@@ -91,60 +227,117 @@ mod tests {
     fn single_state() {
         let mut context = HookContext::default();
 
-        {
-            let hook_context = &mut (0, &mut context);
+        let state = context.render_hook(0.into(), use_state(7));
+        assert_eq!(*state, 7);
+        state.set(9);
 
-            let (state, set_state) = use_state(7).build(hook_context);
+        let state = context.render_hook(0.into(), use_state(7));
+        assert_eq!(*state, 9);
+    }
 
-            assert_eq!(state, Rc::new(7));
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        i: u32,
+        f: f32,
+    }
 
-            set_state(9);
-        }
+    type States = (
+        UseStateHandle<u32>,
+        UseStateHandle<String>,
+        UseStateHandle<Test>,
+    );
 
+    fn render_states(context: &mut HookContext) -> States {
         context.counter = 0;
-        let hook_context = &mut (0, &mut context);
+        let hook_context = &mut (0.into(), context);
+
+        (
+            use_state(7).build(hook_context),
+            use_state("test".to_owned()).build(hook_context),
+            use_state(Test { i: 9, f: 3.4 }).build(hook_context),
+        )
+    }
+
+    #[test]
+    fn multiple_state() {
+        let mut context = HookContext::default();
 
-        let (state, _) = use_state(7).build(hook_context);
+        let (int_state, string_state, struct_state) = render_states(&mut context);
 
-        assert_eq!(state, Rc::new(9));
+        assert_eq!(*int_state, 7);
+        assert_eq!(*string_state, "test".to_owned());
+        assert_eq!(*struct_state, Test { i: 9, f: 3.4 });
+
+        int_state.set(9);
+        string_state.set("test 2".to_owned());
+        struct_state.set(Test { i: 1, f: 6.4 });
+
+        let (int_state, string_state, struct_state) = render_states(&mut context);
+
+        assert_eq!(*int_state, 9);
+        assert_eq!(*string_state, "test 2".to_owned());
+        assert_eq!(*struct_state, Test { i: 1, f: 6.4 });
     }
 
-    #[derive(Debug, PartialEq)]
-    struct Test {
-        i: u32,
-        f: f32,
+    #[test]
+    fn functional_update() {
+        let mut context = HookContext::default();
+
+        let state = context.render_hook(0.into(), use_state(0));
+        state.update(|prev| prev + 1);
+        state.update(|prev| prev + 1);
+        assert_eq!(*state, 0);
+
+        let state = context.render_hook(0.into(), use_state(0));
+        assert_eq!(*state, 2);
     }
 
     #[test]
-    fn multiple_state() {
+    fn lazy_initial_value() {
         let mut context = HookContext::default();
 
-        {
-            let hook_context = &mut (0, &mut context);
+        let calls = Rc::new(RefCell::new(0));
+        let init = || {
+            let calls = calls.clone();
+            move || {
+                *calls.borrow_mut() += 1;
+                7
+            }
+        };
 
-            let (int_state, set_int_state) = use_state(7).build(hook_context);
-            let (string_state, set_string_state) = use_state("test".to_owned()).build(hook_context);
-            let (struct_state, set_struct_state) =
-                use_state(Test { i: 9, f: 3.4 }).build(hook_context);
+        let state = context.render_hook(0.into(), use_state_with(init()));
+        assert_eq!(*state, 7);
 
-            assert_eq!(int_state, Rc::new(7));
-            assert_eq!(string_state, Rc::new("test".to_owned()));
-            assert_eq!(struct_state, Rc::new(Test { i: 9, f: 3.4 }));
+        let state = context.render_hook(0.into(), use_state_with(init()));
+        assert_eq!(*state, 7);
+        assert_eq!(*calls.borrow(), 1);
+    }
 
-            set_int_state(9);
-            set_string_state("test 2".to_owned());
-            set_struct_state(Test { i: 1, f: 6.4 });
-        }
+    #[test]
+    #[allow(deprecated)]
+    fn convert_to_tuple() {
+        let mut context = HookContext::default();
 
-        context.counter = 0;
-        let hook_context = &mut (0, &mut context);
+        let (state, set_state): State<u32> = context.render_hook(0.into(), use_state(7)).into();
+        assert_eq!(*state, 7);
+        set_state(9);
+
+        let state = context.render_hook(0.into(), use_state(7u32));
+        assert_eq!(*state, 9);
+    }
+
+    #[test]
+    fn skip_equal_update() {
+        let mut context = HookContext::default();
+
+        let hook_context = &mut (0.into(), &mut context);
+        let state = use_state_eq(7).build(hook_context);
 
-        let (int_state, _) = use_state(7).build(hook_context);
-        let (string_state, _) = use_state("test".to_owned()).build(hook_context);
-        let (struct_state, _) = use_state(Test { i: 9, f: 3.4 }).build(hook_context);
+        let value = state.hook.0.borrow().value.clone();
+        state.set(7);
+        assert!(Rc::ptr_eq(&value, &state.hook.0.borrow().value));
 
-        assert_eq!(int_state, Rc::new(9));
-        assert_eq!(string_state, Rc::new("test 2".to_owned()));
-        assert_eq!(struct_state, Rc::new(Test { i: 1, f: 6.4 }));
+        state.set(8);
+        assert!(!Rc::ptr_eq(&value, &state.hook.0.borrow().value));
     }
 }
@@ -0,0 +1,258 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::spawn_local;
+use crate::Hook;
+use crate::HookBuilder;
+use crate::HookContext;
+use futures::future::{self, abortable, AbortHandle};
+use futures::{Stream, StreamExt};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+pub struct UseStreamBuilder<F, D, I, R> {
+    factory: F,
+    dependencies: D,
+    initial_value: I,
+    reducer: R,
+}
+
+/// This hook consumes the stream returned by `factory` and re-renders the component for every item.
+///
+/// Returns the latest item of the stream, or `None` until the first item is received.
+///
+/// The stream is created during the first render and every time the `dependencies` change.
+/// When this happens, or when the component is removed from the tree, the previous stream is dropped.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_stream, ElementBuilder};
+/// # use review::Tag::P;
+/// # use futures::stream::{self, Stream};
+/// # fn messages(room: u32) -> impl Stream<Item = String> { stream::iter(vec![format!("joined {}", room)]) }
+/// #[component(LastMessage)]
+/// pub fn last_message(room: &u32) -> VNode {
+///     let message = use_stream(
+///         {
+///             let room = *room;
+///             move || messages(room)
+///         },
+///         *room,
+///     );
+///
+///     match &*message {
+///         Some(message) => P.with_child(message).into(),
+///         None => P.with_child("No messages").into(),
+///     }
+/// }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn use_stream<F, S, T, D>(
+    factory: F,
+    dependencies: D,
+) -> UseStreamBuilder<F, D, fn() -> Option<T>, fn(&Option<T>, T) -> Option<T>>
+where
+    F: FnOnce() -> S,
+    S: Stream<Item = T> + 'static,
+    T: 'static,
+    D: Any + PartialEq,
+{
+    UseStreamBuilder {
+        factory,
+        dependencies,
+        initial_value: || None,
+        reducer: |_, item| Some(item),
+    }
+}
+
+/// Like [use_stream] but folds the items of the stream into an accumulated value with `reducer`.
+///
+/// Returns the accumulated value, which is the one returned by `initial_value` until the first
+/// item is received. `initial_value` is only called when the stream is created, so the value is
+/// reset to a new initial value when the `dependencies` change.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_stream_fold, ElementBuilder};
+/// # use review::Tag::P;
+/// # use futures::stream::{self, Stream};
+/// # fn ticks() -> impl Stream<Item = ()> { stream::iter(vec![(), ()]) }
+/// #[component(Ticks)]
+/// pub fn ticks_counter() -> VNode {
+///     let count = use_stream_fold(ticks, (), || 0, |count, _| count + 1);
+///
+///     P.with_child(format!("{} ticks", count)).into()
+/// }
+/// ```
+pub fn use_stream_fold<F, S, T, D, I, A, R>(
+    factory: F,
+    dependencies: D,
+    initial_value: I,
+    reducer: R,
+) -> UseStreamBuilder<F, D, I, R>
+where
+    F: FnOnce() -> S,
+    S: Stream<Item = T> + 'static,
+    T: 'static,
+    D: Any + PartialEq,
+    I: FnOnce() -> A,
+    A: 'static,
+    R: Fn(&A, T) -> A + 'static,
+{
+    UseStreamBuilder {
+        factory,
+        dependencies,
+        initial_value,
+        reducer,
+    }
+}
+
+struct StreamHook<A, D> {
+    state: Rc<RefCell<Rc<A>>>,
+    dependencies: Option<D>,
+    abort_handle: Option<AbortHandle>,
+}
+
+impl<A, D> Debug for StreamHook<A, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("StreamHook").finish_non_exhaustive()
+    }
+}
+
+impl<A: 'static, D: Any + PartialEq> Hook for StreamHook<A, D> {
+    fn unmount(&mut self) {
+        if let Some(abort_handle) = self.abort_handle.take() {
+            abort_handle.abort();
+        }
+    }
+}
+
+impl<F, S, T, D, I, A, R> HookBuilder<Rc<A>> for UseStreamBuilder<F, D, I, R>
+where
+    F: FnOnce() -> S,
+    S: Stream<Item = T> + 'static,
+    T: 'static,
+    D: Any + PartialEq,
+    I: FnOnce() -> A,
+    A: 'static,
+    R: Fn(&A, T) -> A + 'static,
+{
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> Rc<A> {
+        let mut initial_value = Some(self.initial_value);
+
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let initial_value: StreamHook<A, D> = StreamHook {
+                state: Rc::new(RefCell::new(Rc::new((initial_value.take().unwrap())()))),
+                dependencies: None,
+                abort_handle: None,
+            };
+            hook_context.hooks.push(Box::new(initial_value));
+        }
+
+        let hook: &mut StreamHook<A, D> = hook_context.get_mut_hook(hook_position);
+        if hook.dependencies.as_ref() != Some(&self.dependencies) {
+            hook.unmount();
+            hook.dependencies = Some(self.dependencies);
+            if let Some(initial_value) = initial_value.take() {
+                hook.state.replace(Rc::new(initial_value()));
+            }
+
+            let reducer = self.reducer;
+            let state = hook.state.clone();
+            let fiber_id = *fiber_id;
+            let (future, abort_handle) = abortable((self.factory)().for_each(move |item| {
+                let value = reducer(&state.borrow(), item);
+                state.replace(Rc::new(value));
+                schedule_update(fiber_id);
+
+                future::ready(())
+            }));
+            spawn_local(async move {
+                let _ = future.await;
+            });
+            hook.abort_handle = Some(abort_handle);
+        }
+
+        let state = hook.state.borrow().clone();
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_until_stalled;
+    use futures::channel::mpsc;
+
+    #[test]
+    fn keep_latest_item() {
+        let mut context = HookContext::default();
+        let (sender, receiver) = mpsc::unbounded::<u32>();
+
+        let item = context.render_hook(0.into(), use_stream(move || receiver, ()));
+        assert_eq!(*item, None);
+
+        sender.unbounded_send(1).unwrap();
+        sender.unbounded_send(2).unwrap();
+        run_until_stalled();
+
+        let item = context.render_hook(0.into(), use_stream(futures::stream::empty::<u32>, ()));
+        assert_eq!(*item, Some(2));
+    }
+
+    #[test]
+    fn fold_items() {
+        let mut context = HookContext::default();
+        let (sender, receiver) = mpsc::unbounded::<u32>();
+
+        context.render_hook(
+            0.into(),
+            use_stream_fold(
+                move || receiver,
+                (),
+                Vec::new,
+                |items, item| [items.as_slice(), &[item]].concat(),
+            ),
+        );
+
+        sender.unbounded_send(1).unwrap();
+        sender.unbounded_send(2).unwrap();
+        run_until_stalled();
+
+        let items = context.render_hook(
+            0.into(),
+            use_stream_fold(
+                futures::stream::empty,
+                (),
+                || unreachable!("the value is only created with the stream"),
+                |items: &Vec<u32>, _: u32| items.clone(),
+            ),
+        );
+        assert_eq!(*items, vec![1, 2]);
+    }
+
+    #[test]
+    fn drop_stream_when_dependencies_change() {
+        let mut context = HookContext::default();
+        let (old_sender, old_receiver) = mpsc::unbounded::<u32>();
+        let (new_sender, new_receiver) = mpsc::unbounded::<u32>();
+
+        context.render_hook(0.into(), use_stream(move || old_receiver, 1));
+        old_sender.unbounded_send(1).unwrap();
+        run_until_stalled();
+
+        let item = context.render_hook(0.into(), use_stream(move || new_receiver, 2));
+        assert_eq!(*item, None);
+
+        run_until_stalled();
+        assert!(old_sender.is_closed());
+
+        context.unmount();
+        run_until_stalled();
+        assert!(new_sender.is_closed());
+    }
+}
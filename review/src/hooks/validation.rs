@@ -0,0 +1,260 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::panic::Location;
+
+/// The kind and the call site of a hook
+#[derive(Debug, Clone, Copy)]
+struct HookCall {
+    kind: &'static str,
+    location: &'static Location<'static>,
+}
+
+impl Display for HookCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` called at {}", hook_name(self.kind), self.location)
+    }
+}
+
+/// Records the hooks called by a component to check that every render calls them in the same order
+#[derive(Debug, Default)]
+pub(super) struct HookValidation {
+    component: &'static str,
+    calls: Vec<HookCall>,
+    hook_count: Option<usize>,
+}
+
+impl HookValidation {
+    pub(super) fn start_render(&mut self, component: &'static str) {
+        self.component = component;
+    }
+
+    /// Records the hook created at `hook_position` during the first render that reached it.
+    pub(super) fn record(
+        &mut self,
+        hook_position: usize,
+        kind: &'static str,
+        location: &'static Location<'static>,
+    ) {
+        debug_assert_eq!(hook_position, self.calls.len());
+
+        self.calls.push(HookCall { kind, location });
+    }
+
+    /// Checks that the hook at `hook_position` has the same kind and the same call site
+    /// of the previous renders.
+    ///
+    /// # Panics
+    /// Panics if the hook is a different one, even of the same kind.
+    pub(super) fn check_hook(
+        &mut self,
+        hook_position: usize,
+        kind: &'static str,
+        location: &'static Location<'static>,
+    ) {
+        let call = HookCall { kind, location };
+
+        match self.calls.get(hook_position) {
+            Some(expected) if expected.kind != kind || expected.location != location => panic!(
+                "hooks called in a different order in component `{}`: hook #{} was {} in the previous render, but now it is {}",
+                short_type_name(self.component),
+                hook_position,
+                expected,
+                call
+            ),
+            Some(_) => {}
+            None => self.record(hook_position, kind, location),
+        }
+    }
+
+    /// Checks that the render called as many hooks as the previous renders.
+    ///
+    /// # Panics
+    /// Panics if the number of hooks changed.
+    pub(super) fn finish_render(&mut self, hook_count: usize) {
+        match self.hook_count {
+            Some(previous_count) if previous_count != hook_count => panic!(
+                "component `{}` called {} hooks, but {} hooks were called in the previous render. Expected hooks:\n{}",
+                short_type_name(self.component),
+                hook_count,
+                previous_count,
+                self.calls
+                    .iter()
+                    .enumerate()
+                    .map(|(position, call)| format!("  #{} {}", position, call))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            _ => self.hook_count = Some(hook_count),
+        }
+    }
+}
+
+/// The name of a hook type without module paths and generic arguments
+fn hook_name(kind: &str) -> String {
+    let name = short_type_name(kind);
+    match name.find('<') {
+        Some(generics_start) => name[..generics_start].to_owned(),
+        None => name,
+    }
+}
+
+/// Removes the module paths from a type name, `alloc::vec::Vec<u32>` becomes `Vec<u32>`
+fn short_type_name(name: &str) -> String {
+    let mut short_name = String::with_capacity(name.len());
+    let mut segment_start = 0;
+
+    for (index, c) in name.char_indices() {
+        if c == ':' {
+            if name[index + 1..].starts_with(':') {
+                short_name.truncate(segment_start);
+            }
+        } else {
+            if !(c.is_alphanumeric() || c == '_') {
+                segment_start = short_name.len() + c.len_utf8();
+            }
+            short_name.push(c);
+        }
+    }
+
+    short_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fiber::FiberId;
+    use crate::{
+        use_effect, use_state, AnyComponent, ComponentProvider, HookBuilder, HookContext, VNode,
+    };
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[derive(Debug)]
+    struct Hooks(Vec<bool>);
+
+    impl ComponentProvider for Hooks {
+        type Props = Vec<bool>;
+
+        fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+            for state in props {
+                if *state {
+                    use_state(0).build(context);
+                } else {
+                    use_effect(|| None::<fn()>, None::<()>).build(context);
+                }
+            }
+
+            "".into()
+        }
+
+        fn get_props(&self) -> &Self::Props {
+            &self.0
+        }
+
+        fn get_mut_props(&mut self) -> &mut Self::Props {
+            &mut self.0
+        }
+    }
+
+    /// Calls two states, swapping their order when the prop changes
+    #[derive(Debug)]
+    struct SwappedStates(bool);
+
+    impl ComponentProvider for SwappedStates {
+        type Props = bool;
+
+        fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+            if *props {
+                use_state(0).build(context);
+            }
+            use_state(1).build(context);
+            if !*props {
+                use_state(0).build(context);
+            }
+
+            "".into()
+        }
+
+        fn get_props(&self) -> &Self::Props {
+            &self.0
+        }
+
+        fn get_mut_props(&mut self) -> &mut Self::Props {
+            &mut self.0
+        }
+    }
+
+    fn render_error<C: ComponentProvider + 'static>(renders: Vec<C>) -> String {
+        let mut hook_context = HookContext::default();
+        let error = catch_unwind(AssertUnwindSafe(|| {
+            for component in renders {
+                component.render(&mut (0.into(), &mut hook_context));
+            }
+        }))
+        .expect_err("render should fail");
+
+        error.downcast_ref::<String>().cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn same_order_is_valid() {
+        let mut hook_context = HookContext::default();
+        for _ in 0..2 {
+            Hooks(vec![true, false]).render(&mut (0.into(), &mut hook_context));
+        }
+    }
+
+    #[test]
+    fn different_order_fails() {
+        let error = render_error(vec![Hooks(vec![true, false]), Hooks(vec![false, true])]);
+
+        assert!(error.starts_with(
+            "hooks called in a different order in component `Hooks`: hook #0 was `StateHook` called at"
+        ));
+        assert!(error.contains(file!()));
+    }
+
+    #[test]
+    fn same_kind_in_different_order_fails() {
+        let error = render_error(vec![SwappedStates(true), SwappedStates(false)]);
+
+        let locations: Vec<_> = error
+            .split("`StateHook` called at ")
+            .skip(1)
+            .map(|call| call.split_whitespace().next().unwrap_or_default())
+            .collect();
+        assert_eq!(locations.len(), 2);
+        assert!(locations.iter().all(|location| location.contains(file!())));
+        assert_ne!(locations[0], locations[1]);
+    }
+
+    #[test]
+    fn different_count_fails() {
+        let error = render_error(vec![Hooks(vec![true, false]), Hooks(vec![true])]);
+
+        assert!(error.starts_with(
+            "component `Hooks` called 1 hooks, but 2 hooks were called in the previous render"
+        ));
+        assert!(error.contains("#1 `EffectHook` called at"));
+    }
+
+    #[test]
+    fn hook_names() {
+        assert_eq!(
+            hook_name("review::hooks::use_state::StateHook<alloc::vec::Vec<u32>>"),
+            "StateHook"
+        );
+        assert_eq!(short_type_name("review::signal::SignalHook"), "SignalHook");
+    }
+
+    #[test]
+    fn shorten_type_names() {
+        assert_eq!(
+            short_type_name("review::hooks::use_state::StateCell<alloc::vec::Vec<u32>>"),
+            "StateCell<Vec<u32>>"
+        );
+        assert_eq!(
+            short_type_name("(u32, core::option::Option<()>)"),
+            "(u32, Option<()>)"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::spawn_local;
+use crate::{ComponentProvider, Hook, HookBuilder, HookContext, VNode};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+type LazyRender<P> = Rc<dyn Fn(P) -> VNode>;
+type LazyLoader<P> = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = LazyRender<P>>>>>;
+
+enum LazyState<P> {
+    NotLoaded,
+    Loading(Vec<FiberId>),
+    Loaded(LazyRender<P>),
+}
+
+/// A component that is loaded asynchronously the first time it's rendered.
+///
+/// It's created with the [lazy] function and can be cloned to share the loaded component.
+pub struct Lazy<P> {
+    loader: LazyLoader<P>,
+    state: Rc<RefCell<LazyState<P>>>,
+}
+
+/// Creates a [Lazy] component from an async `loader`.
+///
+/// The `loader` resolves to a function that creates the component from its props,
+/// so a component generated with the `component` attribute could be returned directly.
+/// The loader is called only when the component is rendered for the first time,
+/// for example to fetch a code-split wasm module.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, lazy, use_state_with, ElementBuilder};
+/// # use review::Tag::{Div, P};
+/// # #[derive(Debug, Clone)]
+/// # pub struct DashboardProps { user: String }
+/// #[component(Dashboard)]
+/// pub fn dashboard(props: &DashboardProps) -> VNode {
+///     P.with_child(format!("Hello {}", props.user)).into()
+/// }
+///
+/// #[component(App)]
+/// pub fn app() -> VNode {
+///     let dashboard = use_state_with(|| lazy(|| async { Dashboard }));
+///
+///     Div.with_child(
+///         dashboard
+///             .with_props(DashboardProps { user: "reView".to_owned() })
+///             .with_fallback("Loading..."),
+///     )
+///     .into()
+/// }
+/// ```
+pub fn lazy<P, L, Fut, F, C>(loader: L) -> Lazy<P>
+where
+    L: Fn() -> Fut + 'static,
+    Fut: Future<Output = F> + 'static,
+    F: Fn(P) -> C + 'static,
+    C: Into<VNode>,
+{
+    Lazy {
+        loader: Rc::new(move || {
+            let future = loader();
+            Box::pin(async move {
+                let create = future.await;
+                Rc::new(move |props| create(props).into()) as LazyRender<P>
+            })
+        }),
+        state: Rc::new(RefCell::new(LazyState::NotLoaded)),
+    }
+}
+
+impl<P> Lazy<P> {
+    /// Creates a [LazyComponent] that renders the loaded component with the given props
+    pub fn with_props(&self, props: P) -> LazyComponent<P> {
+        LazyComponent(LazyProps {
+            lazy: self.clone(),
+            props,
+            fallback: "".into(),
+        })
+    }
+
+    /// Returns true if the component is loaded
+    pub fn is_loaded(&self) -> bool {
+        matches!(*self.state.borrow(), LazyState::Loaded(_))
+    }
+}
+
+impl<P> Clone for Lazy<P> {
+    fn clone(&self) -> Self {
+        Self {
+            loader: self.loader.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<P> Debug for Lazy<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lazy")
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+#[doc(hidden)]
+pub struct LazyProps<P> {
+    lazy: Lazy<P>,
+    props: P,
+    fallback: VNode,
+}
+
+/// The component created from a [Lazy] with [Lazy::with_props]
+///
+/// It renders the `fallback` until the component is loaded.
+pub struct LazyComponent<P>(LazyProps<P>);
+
+impl<P> LazyComponent<P> {
+    /// Sets the node rendered while the component is loading
+    pub fn with_fallback<T: Into<VNode>>(mut self, fallback: T) -> Self {
+        self.0.fallback = fallback.into();
+
+        self
+    }
+}
+
+impl<P> Debug for LazyComponent<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyComponent")
+            .field("lazy", &self.0.lazy)
+            .finish()
+    }
+}
+
+struct LazyHook<P> {
+    state: Rc<RefCell<LazyState<P>>>,
+    fiber_id: FiberId,
+}
+
+impl<P> Debug for LazyHook<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("LazyHook")
+            .field("fiber_id", &self.fiber_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: 'static> Hook for LazyHook<P> {
+    fn unmount(&mut self) {
+        if let LazyState::Loading(waiting) = &mut *self.state.borrow_mut() {
+            waiting.retain(|fiber_id| *fiber_id != self.fiber_id);
+        }
+    }
+}
+
+struct UseLazyBuilder<P> {
+    lazy: Lazy<P>,
+}
+
+impl<P: 'static> HookBuilder<Option<LazyRender<P>>> for UseLazyBuilder<P> {
+    fn build(
+        self,
+        (fiber_id, hook_context): &mut (FiberId, &mut HookContext),
+    ) -> Option<LazyRender<P>> {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            hook_context.hooks.push(Box::new(LazyHook {
+                state: self.lazy.state.clone(),
+                fiber_id: *fiber_id,
+            }));
+        }
+
+        let hook: &mut LazyHook<P> = hook_context.get_mut_hook(hook_position);
+        if !Rc::ptr_eq(&hook.state, &self.lazy.state) {
+            hook.unmount();
+            hook.state = self.lazy.state.clone();
+        }
+
+        let mut state = self.lazy.state.borrow_mut();
+        match &mut *state {
+            LazyState::Loaded(render) => Some(render.clone()),
+            LazyState::Loading(waiting) => {
+                if !waiting.contains(fiber_id) {
+                    waiting.push(*fiber_id);
+                }
+
+                None
+            }
+            LazyState::NotLoaded => {
+                *state = LazyState::Loading(vec![*fiber_id]);
+
+                let future = (self.lazy.loader)();
+                let state = self.lazy.state.clone();
+                spawn_local(async move {
+                    let render = future.await;
+                    let waiting = state.replace(LazyState::Loaded(render));
+                    if let LazyState::Loading(waiting) = waiting {
+                        for fiber_id in waiting {
+                            schedule_update(fiber_id);
+                        }
+                    }
+                });
+
+                None
+            }
+        }
+    }
+}
+
+impl<P: Clone + 'static> ComponentProvider for LazyComponent<P> {
+    type Props = LazyProps<P>;
+
+    fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+        match (UseLazyBuilder {
+            lazy: props.lazy.clone(),
+        })
+        .build(context)
+        {
+            Some(render) => render(props.props.clone()),
+            None => props.fallback.clone(),
+        }
+    }
+
+    fn get_props(&self) -> &Self::Props {
+        &self.0
+    }
+
+    fn get_mut_props(&mut self) -> &mut Self::Props {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{commit_work, work_loop, App};
+    use crate::fiber::FiberTree;
+    use crate::node::{Element, Node};
+    use crate::run_until_stalled;
+    use crate::Events;
+    use crate::Tag;
+    use std::collections::HashMap;
+
+    use crate as review;
+    use review::*;
+
+    #[component(Greeting)]
+    fn greeting(name: &String) -> VNode {
+        format!("Hello {}", name).into()
+    }
+
+    fn rendered_text(app: &App, root_id: FiberId) -> String {
+        let mut id = root_id;
+        while let Some(child_id) = app.fiber_tree.get(id).and_then(|n| n.child) {
+            id = child_id;
+        }
+
+        match app.fiber_tree.get(id).map(|n| &n.node) {
+            Some(Node::Text(text)) => text.text.clone(),
+            _ => panic!("not a text node"),
+        }
+    }
+
+    #[test]
+    fn render_fallback_until_loaded() {
+        let greeting_lazy = lazy(|| async { Greeting });
+
+        let mut fiber_tree = FiberTree::default();
+        let root_id = fiber_tree.new_node(Node::Element(Element {
+            dom: None,
+            tag: Tag::Empty,
+            attributes: HashMap::with_capacity(0),
+            events: Events(HashMap::with_capacity(0)),
+            unprocessed_children: vec![greeting_lazy
+                .with_props("reView".to_owned())
+                .with_fallback("loading")
+                .into()],
+        }));
+        let mut app = App {
+            fiber_tree,
+            next_unit_of_work: Some(root_id),
+            wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
+            document: None,
+        };
+
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        assert!(!greeting_lazy.is_loaded());
+        assert_eq!(rendered_text(&app, root_id), "loading");
+
+        run_until_stalled();
+        assert!(greeting_lazy.is_loaded());
+
+        let lazy_id = app.fiber_tree.get(root_id).and_then(|n| n.child).unwrap();
+        app.wip_root = Some(lazy_id);
+        app.next_unit_of_work = Some(lazy_id);
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        assert_eq!(rendered_text(&app, root_id), "Hello reView");
+    }
+}
@@ -19,13 +19,13 @@
 //!
 //! #[component(App)]
 //! pub fn app() -> VNode {
-//!     let (state, set_state) = use_state(0);
+//!     let state = use_state(0);
 //!
 //!     Div.with_children(children!(
-//!         format!("Current value {}", state),
+//!         format!("Current value {}", *state),
 //!         Button
 //!             .with_child("Increase counter")
-//!             .with_event(OnClick, callback!(move || { set_state(*state + 1) }))
+//!             .with_event(OnClick, callback!(move || state.update(|prev| prev + 1)))
 //!     ))
 //!     .into()
 //! }
@@ -38,22 +38,41 @@
 //! ```
 
 mod app;
+mod callback;
+mod children;
 mod component;
 mod events;
 mod fiber;
 mod hooks;
+mod lazy;
 mod node;
+mod program;
+mod props;
 mod reconciliation;
+mod scheduler;
+mod signal;
+mod store;
+mod struct_component;
+mod suspense;
 mod tag;
 mod utils;
 mod vdom;
 
 pub use app::*;
+pub use callback::*;
+pub use children::*;
 pub use component::*;
 pub use events::*;
-pub use fiber::FiberId;
+pub use fiber::{BenchTree, FiberId};
 pub use hooks::HookContext;
 pub use hooks::*;
+pub use lazy::{lazy, Lazy, LazyComponent, LazyProps};
+pub use program::{program, Cmd, Dispatcher};
+pub use props::*;
+pub use signal::{use_signal, Signal};
+pub use store::{logger, persist, use_selector, Middleware, Store};
+pub use struct_component::{Link, ShouldRender, StructComponent, StructNode};
+pub use suspense::{Suspense, SuspenseProps};
 pub use tag::*;
 pub use utils::*;
 pub use vdom::*;
@@ -62,15 +81,22 @@ pub use log;
 
 #[doc(hidden)]
 pub use wasm_bindgen::closure::Closure;
+pub use wasm_bindgen::JsValue;
 
 extern crate review_macro;
+extern crate self as review;
 /// This attribute creates a component from a normal Rust function.
 ///
-/// Functions with this attribute must return a [VNode] and can optionally take an argument for props.
-/// Note that the function only receives a reference to the props.
+/// Functions with this attribute must return a [VNode] and can take the props in two ways:
+/// - a single reference to a props type, like `props: &BoardProps`
+/// - any number of named arguments. The attribute generates a props struct, named after the
+///   component with a `Props` suffix, with a public field for every argument, and a `new`
+///   constructor that takes the arguments in order.
+///   The arguments are cloned from the props at every render, so they must implement [Clone].
 ///
-/// When using this attribute you need to provide a name for the component: `#[component(ComponentName)]`.
-/// The attribute will then automatically create a Component with the given identifier which you can use like a normal struct.
+/// The attribute creates a Component that can be used like a normal struct.
+/// Its name can be provided with `#[component(ComponentName)]`, otherwise it's the name of
+/// the function in PascalCase.
 ///
 /// # Example
 /// ```rust
@@ -83,6 +109,25 @@ extern crate review_macro;
 ///     P.with_child(format!("{:?}", props)).into()
 /// }
 /// ```
+///
+/// With named arguments
+/// ```rust
+/// # use review::{component, html, VNode};
+/// #[component]
+/// pub fn greeting(name: String, excited: bool) -> VNode {
+///     let punctuation = if excited { "!" } else { "." };
+///
+///     html! { <p>{format!("Hello {}{}", name, punctuation)}</p> }
+/// }
+///
+/// let greeting: VNode = Greeting::new("reView".to_owned(), true).into();
+/// let same_greeting: VNode = Greeting(GreetingProps {
+///     name: "reView".to_owned(),
+///     excited: true,
+/// })
+/// .into();
+/// let with_html = html! { <Greeting name={"reView".to_owned()} excited={true} /> };
+/// ```
 pub use review_macro::component;
 
 /// This attribute creates a user-defined hook from a normal Rust function.
@@ -92,23 +137,93 @@ pub use review_macro::component;
 ///
 /// # Example
 /// ```rust
-/// # use review::{hook, State, use_state, use_effect, log};
+/// # use review::{hook, UseStateHandle, use_state, use_effect, log};
 /// #[hook]
-/// pub fn use_example() -> State<u32> {
-///     let (c, set_c) = use_state(5);
+/// pub fn use_example() -> UseStateHandle<u32> {
+///     let c = use_state(5);
 ///     use_effect(
 ///         {
 ///             let c = c.clone();
 ///             move || {
-///                 log::info!("{}", c);
+///                 log::info!("{}", *c);
 ///
 ///                 None::<fn()>
 ///             }
 ///         },
-///         Some(c.clone()),
+///         Some(*c),
 ///     );
 ///
-///     (c, set_c)
+///     c
 /// }
 /// ```
 pub use review_macro::hook;
+
+/// This macro creates a [VNode] from a markup-like syntax similar to JSX.
+///
+/// It expands to the [ElementBuilder] API, so it accepts:
+/// - elements named after a [Tag] in lowercase, like `<div>...</div>` or `<br />`
+/// - attributes with a string literal, like `class="board"`, with any expression implementing
+///   `ToString` in braces, like `id={format!("row-{}", index)}`, or without a value, like `disabled`
+/// - events named after an [EventType] in snake_case, like `on_click={callback!(|| ...)}`
+/// - components named in PascalCase, like `<Square value={value} on_click={on_click} />`.
+///   Every attribute of a component sets a field of its props with their [Properties](trait@Properties)
+///   builder, or the remaining fields can be taken from another value with `..{props}`.
+///   The children of a component set its [Children] prop.
+/// - children that are string literals, expressions implementing `Into<VNode>` in braces, like `{status}`,
+///   and iterators of them, like `{for moves}`
+///
+/// # Example
+/// ```rust
+/// # use review::{callback, component, html, log, VNode};
+/// #[component(Menu)]
+/// pub fn menu() -> VNode {
+///     let entries = vec!["Home", "About"];
+///
+///     html! {
+///         <nav class="menu" data-entries={entries.len()}>
+///             <h1>"Menu"</h1>
+///             <ul>
+///                 {for entries.iter().map(|entry| html! { <li>{*entry}</li> })}
+///             </ul>
+///             <button on_click={callback!(|| log::info!("closed"))}>"Close"</button>
+///         </nav>
+///     }
+/// }
+/// ```
+pub use review_macro::html;
+
+/// This derive implements [Properties](trait@Properties) for a struct with named fields,
+/// generating a builder with a setter for each field, and `PartialEq` so that the props can be compared.
+/// The `#[props(no_eq)]` attribute on the struct skips the `PartialEq` implementation, so that it can be
+/// derived or written by hand instead.
+///
+/// Every field is required unless it has a `#[prop(...)]` attribute with one of these options:
+/// - `default` makes the field fall back to `Default::default()` when it isn't set, and
+///   `default = expr` makes it fall back to `expr`
+/// - `optional` makes an `Option<T>` field fall back to `None`, and its setter takes a `T`
+///
+/// The `into` option makes the setter accept any value that implements `Into` the type of the field.
+///
+/// The `build` method of the builder is only available when every required field is set, so a
+/// missing field is reported at compile time by an error that names the `Missing<{field}>` type.
+///
+/// # Example
+/// ```rust
+/// # use review::{component, html, Properties, VNode};
+/// #[derive(Properties)]
+/// pub struct TitleProps {
+///     #[prop(into)]
+///     text: String,
+///     #[prop(default = 1)]
+///     level: u8,
+/// }
+///
+/// #[component(Title)]
+/// pub fn title(props: &TitleProps) -> VNode {
+///     html! { <h1 data-level={props.level}>{&*props.text}</h1> }
+/// }
+///
+/// let title = html! { <Title text="reView" /> };
+/// assert!(TitleProps::builder().text("a").build() == TitleProps::builder().text("a").level(1).build());
+/// ```
+pub use review_macro::Properties;
@@ -1,3 +1,4 @@
+use crate::fiber::UpdateData;
 use crate::{AnyComponent, Events, HookContext, Tag, VNode};
 use std::{collections::HashMap, rc::Rc};
 use wasm_bindgen::JsCast;
@@ -116,6 +117,31 @@ pub(crate) enum Node {
 }
 
 impl Node {
+    pub(crate) fn update(&mut self, update_data: UpdateData) {
+        match (self, update_data) {
+            (Node::Element(element), UpdateData::Element { attributes, events }) => {
+                element.update_element_dom(attributes, events)
+            }
+            (Node::Text(text), UpdateData::Text(new_text)) => text.update_text_dom(new_text),
+            (Node::Component(Component { function, .. }), UpdateData::Component(new_component)) => {
+                *function = new_component;
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the update would leave the node as it is.
+    /// A component is never up to date, because its props have to be replaced.
+    pub(crate) fn is_up_to_date(&self, update_data: &UpdateData) -> bool {
+        match (self, update_data) {
+            (Node::Element(element), UpdateData::Element { attributes, events }) => {
+                element.attributes == *attributes && element.events == *events
+            }
+            (Node::Text(text), UpdateData::Text(new_text)) => text.text == *new_text,
+            _ => false,
+        }
+    }
+
     pub(crate) fn create_dom(&mut self, document: Option<&web_sys::Document>) {
         if let Some(document) = document {
             match self {
@@ -0,0 +1,367 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::spawn_local;
+use crate::{Callback, ComponentProvider, Hook, HookBuilder, HookContext, VNode};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+
+enum Command<Msg> {
+    Msg(Msg),
+    Future(Pin<Box<dyn Future<Output = Msg>>>),
+}
+
+/// The effects requested by `init` and `update` in a [program], whose results are messages
+/// sent back to `update`
+pub struct Cmd<Msg> {
+    commands: Vec<Command<Msg>>,
+}
+
+impl<Msg: 'static> Cmd<Msg> {
+    /// No effects
+    pub fn none() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Sends `msg` to `update` right after the current update
+    pub fn msg(msg: Msg) -> Self {
+        Self {
+            commands: vec![Command::Msg(msg)],
+        }
+    }
+
+    /// Runs `future` and sends its output to `update`
+    pub fn perform<F: Future<Output = Msg> + 'static>(future: F) -> Self {
+        Self {
+            commands: vec![Command::Future(Box::pin(future))],
+        }
+    }
+
+    /// Runs all the commands
+    pub fn batch(cmds: impl IntoIterator<Item = Cmd<Msg>>) -> Self {
+        Self {
+            commands: cmds.into_iter().flat_map(|cmd| cmd.commands).collect(),
+        }
+    }
+
+    /// Returns `true` if there are no effects
+    pub fn is_none(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl<Msg: 'static> Default for Cmd<Msg> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<Msg> Debug for Cmd<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cmd")
+            .field("commands", &self.commands.len())
+            .finish()
+    }
+}
+
+/// Sends messages to the `update` function of a [program]
+pub struct Dispatcher<Msg> {
+    messages: Rc<RefCell<VecDeque<Msg>>>,
+    fiber_id: FiberId,
+}
+
+impl<Msg: 'static> Dispatcher<Msg> {
+    /// Sends a message to `update`, it's handled in the next render of the program
+    pub fn dispatch(&self, msg: Msg) {
+        self.messages.borrow_mut().push_back(msg);
+
+        schedule_update(self.fiber_id);
+    }
+
+    /// Creates a [Callback] that sends the message returned by `function`
+    pub fn callback<IN, F>(&self, function: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> Msg + 'static,
+    {
+        let dispatcher = self.clone();
+
+        Callback::from(move |value| dispatcher.dispatch(function(value)))
+    }
+
+    fn run(&self, cmd: Cmd<Msg>) {
+        for command in cmd.commands {
+            match command {
+                // Handled by the update in progress, without scheduling another render
+                Command::Msg(msg) => self.messages.borrow_mut().push_back(msg),
+                Command::Future(future) => {
+                    let dispatcher = self.clone();
+                    spawn_local(async move { dispatcher.dispatch(future.await) });
+                }
+            }
+        }
+    }
+}
+
+impl<Msg> Clone for Dispatcher<Msg> {
+    fn clone(&self) -> Self {
+        Self {
+            messages: self.messages.clone(),
+            fiber_id: self.fiber_id,
+        }
+    }
+}
+
+impl<Msg> Debug for Dispatcher<Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("fiber_id", &self.fiber_id)
+            .finish()
+    }
+}
+
+/// Creates an app with the Elm architecture, that runs on the same reconciler of the components.
+///
+/// - `init` creates the initial `Model` and the commands to run when the program starts
+/// - `update` handles a message, changing the `Model`, and returns the commands to run
+/// - `view` renders the `Model`, sending messages with the [Dispatcher]
+///
+/// # Example
+/// ```rust,no_run
+/// # use review::{html, program, Cmd, Dispatcher, VNode};
+/// # async fn fetch_count() -> u32 { 7 }
+/// enum Msg {
+///     Increment,
+///     Fetch,
+///     Fetched(u32),
+/// }
+///
+/// struct Model {
+///     count: u32,
+/// }
+///
+/// fn init() -> (Model, Cmd<Msg>) {
+///     (Model { count: 0 }, Cmd::msg(Msg::Fetch))
+/// }
+///
+/// fn update(model: &mut Model, msg: Msg) -> Cmd<Msg> {
+///     match msg {
+///         Msg::Increment => model.count += 1,
+///         Msg::Fetch => return Cmd::perform(async { Msg::Fetched(fetch_count().await) }),
+///         Msg::Fetched(count) => model.count = count,
+///     }
+///
+///     Cmd::none()
+/// }
+///
+/// fn view(model: &Model, dispatcher: &Dispatcher<Msg>) -> VNode {
+///     html! {
+///         <div>
+///             {model.count.to_string()}
+///             <button on_click={dispatcher.callback(|_: ()| Msg::Increment)}>"+"</button>
+///         </div>
+///     }
+/// }
+///
+/// review::render(program(init, update, view), "root");
+/// ```
+pub fn program<Model, Msg, I, U, V>(init: I, update: U, view: V) -> VNode
+where
+    Model: 'static,
+    Msg: 'static,
+    I: Fn() -> (Model, Cmd<Msg>) + 'static,
+    U: Fn(&mut Model, Msg) -> Cmd<Msg> + 'static,
+    V: Fn(&Model, &Dispatcher<Msg>) -> VNode + 'static,
+{
+    Program {
+        init,
+        update,
+        view,
+        types: PhantomData,
+    }
+    .into()
+}
+
+struct Program<Model, Msg, I, U, V> {
+    init: I,
+    update: U,
+    view: V,
+    types: PhantomData<fn() -> (Model, Msg)>,
+}
+
+impl<Model, Msg, I, U, V> Debug for Program<Model, Msg, I, U, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Program").finish()
+    }
+}
+
+impl<Model, Msg, I, U, V> ComponentProvider for Program<Model, Msg, I, U, V>
+where
+    Model: 'static,
+    Msg: 'static,
+    I: Fn() -> (Model, Cmd<Msg>) + 'static,
+    U: Fn(&mut Model, Msg) -> Cmd<Msg> + 'static,
+    V: Fn(&Model, &Dispatcher<Msg>) -> VNode + 'static,
+{
+    type Props = Self;
+
+    fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+        UseProgramBuilder { program: props }.build(context)
+    }
+
+    fn get_props(&self) -> &Self::Props {
+        self
+    }
+
+    fn get_mut_props(&mut self) -> &mut Self::Props {
+        self
+    }
+}
+
+struct ProgramHook<Model, Msg> {
+    model: Model,
+    dispatcher: Dispatcher<Msg>,
+    /// The last output of `view`, reused when there are no messages to handle
+    view: Option<VNode>,
+}
+
+impl<Model, Msg> Debug for ProgramHook<Model, Msg> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgramHook")
+            .field("model", &std::any::type_name::<Model>())
+            .finish()
+    }
+}
+
+impl<Model: 'static, Msg: 'static> Hook for ProgramHook<Model, Msg> {}
+
+struct UseProgramBuilder<'a, Model, Msg, I, U, V> {
+    program: &'a Program<Model, Msg, I, U, V>,
+}
+
+impl<'a, Model, Msg, I, U, V> HookBuilder<VNode> for UseProgramBuilder<'a, Model, Msg, I, U, V>
+where
+    Model: 'static,
+    Msg: 'static,
+    I: Fn() -> (Model, Cmd<Msg>) + 'static,
+    U: Fn(&mut Model, Msg) -> Cmd<Msg> + 'static,
+    V: Fn(&Model, &Dispatcher<Msg>) -> VNode + 'static,
+{
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> VNode {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let (model, cmd) = (self.program.init)();
+            let dispatcher = Dispatcher {
+                messages: Rc::default(),
+                fiber_id: *fiber_id,
+            };
+            dispatcher.run(cmd);
+
+            hook_context.hooks.push(Box::new(ProgramHook {
+                model,
+                dispatcher,
+                view: None,
+            }));
+        }
+
+        let hook: &mut ProgramHook<Model, Msg> = hook_context.get_mut_hook(hook_position);
+
+        loop {
+            let msg = hook.dispatcher.messages.borrow_mut().pop_front();
+            match msg {
+                Some(msg) => {
+                    let cmd = (self.program.update)(&mut hook.model, msg);
+                    hook.dispatcher.run(cmd);
+                    hook.view = None;
+                }
+                None => break,
+            }
+        }
+
+        let ProgramHook {
+            model,
+            dispatcher,
+            view,
+        } = hook;
+
+        view.get_or_insert_with(|| (self.program.view)(model, dispatcher))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::take_scheduled_updates;
+    use crate::{run_until_stalled, ElementBuilder, Tag::Span};
+
+    thread_local! {
+        static DISPATCHER: RefCell<Option<Dispatcher<Msg>>> = RefCell::new(None);
+    }
+
+    enum Msg {
+        Add(u32),
+        Fetch,
+    }
+
+    fn init() -> (u32, Cmd<Msg>) {
+        (0, Cmd::msg(Msg::Add(1)))
+    }
+
+    fn update(model: &mut u32, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Add(value) => {
+                *model += value;
+                Cmd::none()
+            }
+            Msg::Fetch => Cmd::batch([
+                Cmd::perform(async { Msg::Add(10) }),
+                Cmd::msg(Msg::Add(100)),
+            ]),
+        }
+    }
+
+    fn view(model: &u32, dispatcher: &Dispatcher<Msg>) -> VNode {
+        DISPATCHER.with(|handle| handle.replace(Some(dispatcher.clone())));
+
+        Span.with_child(model.to_string()).into()
+    }
+
+    #[test]
+    fn update_is_pure() {
+        let mut model = 5;
+
+        assert!(update(&mut model, Msg::Add(2)).is_none());
+        assert_eq!(model, 7);
+        assert!(!update(&mut model, Msg::Fetch).is_none());
+        assert_eq!(model, 7);
+    }
+
+    #[test]
+    fn messages_and_commands() {
+        let mut context = HookContext::default();
+        let program = program(init, update, view);
+        let mut render = || match &program {
+            VNode::Component(component) => component.render(&mut (3.into(), &mut context)),
+            _ => panic!("expected a component"),
+        };
+
+        // The message of `init` is handled before the first view
+        assert_eq!(render(), Span.with_child("1").into());
+        assert!(take_scheduled_updates().is_empty());
+
+        DISPATCHER.with(|handle| handle.borrow().as_ref().unwrap().dispatch(Msg::Fetch));
+        assert_eq!(take_scheduled_updates(), vec![3.into()]);
+        assert_eq!(render(), Span.with_child("101").into());
+
+        run_until_stalled();
+        assert_eq!(take_scheduled_updates(), vec![3.into()]);
+        assert_eq!(render(), Span.with_child("111").into());
+    }
+}
@@ -0,0 +1,212 @@
+/// Props that can be created with a builder.
+///
+/// It's implemented by `#[derive(Properties)]` and by the props generated by the
+/// [component](crate::component) attribute, and it's used by the [html!](crate::html) macro
+/// to create the props of a component from its attributes.
+///
+/// # Example
+/// ```rust
+/// # use review::Properties;
+/// #[derive(Properties)]
+/// pub struct ButtonProps {
+///     label: String,
+///     #[prop(default)]
+///     disabled: bool,
+///     #[prop(optional)]
+///     title: Option<String>,
+///     #[prop(into, default = "button".to_owned())]
+///     class: String,
+/// }
+///
+/// let props = ButtonProps::builder()
+///     .label("Save".to_owned())
+///     .title("Save the document".to_owned())
+///     .build();
+///
+/// assert_eq!(props.disabled, false);
+/// assert_eq!(props.title.as_deref(), Some("Save the document"));
+/// assert_eq!(props.class, "button");
+/// ```
+///
+/// A required field that is never set is a compile error
+/// ```rust,compile_fail
+/// # use review::Properties;
+/// #[derive(Properties)]
+/// pub struct ButtonProps {
+///     label: String,
+/// }
+///
+/// let props = ButtonProps::builder().build();
+/// ```
+pub trait Properties {
+    /// The builder of the props, it starts with every required field missing
+    type Builder;
+
+    /// Creates a builder for the props
+    fn builder() -> Self::Builder;
+}
+
+impl Properties for () {
+    type Builder = EmptyPropsBuilder;
+
+    fn builder() -> Self::Builder {
+        EmptyPropsBuilder
+    }
+}
+
+/// The builder of the components without props
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct EmptyPropsBuilder;
+
+impl EmptyPropsBuilder {
+    pub fn build(self) {}
+}
+
+/// A required field set in a props builder
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct PropValue<T>(pub T);
+
+/// Implemented only by the required fields that are set, so a props builder can't be built
+/// while a required field is missing
+#[doc(hidden)]
+pub trait RequiredProp<T> {
+    fn value(self) -> T;
+}
+
+impl<T> RequiredProp<T> for PropValue<T> {
+    fn value(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as review;
+    use crate::Tag::Span;
+    use crate::{component, html, ElementBuilder, HookContext, Properties, VNode};
+
+    #[derive(Debug, Properties)]
+    struct ButtonProps {
+        #[prop(into)]
+        label: String,
+        size: u32,
+        #[prop(default)]
+        disabled: bool,
+        #[prop(default = "button".to_owned())]
+        class: String,
+        #[prop(optional, into)]
+        title: Option<String>,
+    }
+
+    #[derive(Debug, Properties)]
+    struct ListProps<T> {
+        items: Vec<T>,
+        #[prop(default)]
+        selected: Option<usize>,
+    }
+
+    #[component]
+    fn badge(count: u32, #[prop(default = "items".to_owned())] label: String) -> VNode {
+        Span.with_child(format!("{} {}", count, label)).into()
+    }
+
+    #[test]
+    fn builder() {
+        let props = ButtonProps::builder().size(2).label("Save").build();
+
+        assert_eq!(props.label, "Save");
+        assert_eq!(props.size, 2);
+        assert!(!props.disabled);
+        assert_eq!(props.class, "button");
+        assert_eq!(props.title, None);
+
+        let props = ButtonProps::builder()
+            .disabled(true)
+            .label("Save")
+            .class("primary".to_owned())
+            .title("Save the document")
+            .size(1)
+            .build();
+
+        assert!(props.disabled);
+        assert_eq!(props.class, "primary");
+        assert_eq!(props.title.as_deref(), Some("Save the document"));
+    }
+
+    #[test]
+    fn partial_eq() {
+        let props = ButtonProps::builder().size(2).label("Save").build();
+
+        assert_eq!(props, ButtonProps::builder().label("Save").size(2).build());
+        assert_ne!(props, ButtonProps::builder().label("Save").size(3).build());
+        assert_ne!(
+            props,
+            ButtonProps::builder()
+                .label("Save")
+                .size(2)
+                .disabled(true)
+                .build()
+        );
+
+        let list = ListProps::builder().items(vec![1, 2]).build();
+        assert_eq!(list, ListProps::builder().items(vec![1, 2]).build());
+        assert_ne!(
+            list,
+            ListProps::builder()
+                .items(vec![1, 2])
+                .selected(Some(0))
+                .build()
+        );
+    }
+
+    #[test]
+    fn generic_props() {
+        let list = ListProps::builder().items(vec![1, 2]).build();
+        assert_eq!(list.items, vec![1, 2]);
+        assert_eq!(list.selected, None);
+
+        let list = ListProps::builder()
+            .selected(Some(0))
+            .items(vec!["first"])
+            .build();
+        assert_eq!(list.selected, Some(0));
+    }
+
+    /// `no_eq` skips the generated `PartialEq`, so it can be derived instead
+    #[derive(Debug, PartialEq, Properties)]
+    #[props(no_eq)]
+    struct PointProps {
+        x: u32,
+        #[prop(default)]
+        y: u32,
+    }
+
+    #[test]
+    fn derive_partial_eq() {
+        let point = PointProps::builder().x(1).build();
+
+        assert_eq!(point, PointProps::builder().x(1).y(0).build());
+        assert_ne!(point, PointProps::builder().x(1).y(2).build());
+    }
+
+    #[test]
+    fn component_props() {
+        let render = |vnode: VNode| match vnode {
+            VNode::Component(component) => {
+                component.render(&mut (0.into(), &mut HookContext::default()))
+            }
+            _ => panic!("expected a component"),
+        };
+
+        assert_eq!(
+            render(html! { <Badge count={3} /> }),
+            Span.with_child("3 items").into()
+        );
+        assert_eq!(
+            render(html! { <Badge count={1} label={"item".to_owned()} /> }),
+            Span.with_child("1 item").into()
+        );
+    }
+}
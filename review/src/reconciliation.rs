@@ -1,29 +1,81 @@
 use crate::fiber::{EffectTag, FiberId, FiberNode, FiberTree, UpdateData};
 use crate::node::{Component, Element, Node, Text};
+use crate::suspense::{resume, resume_subtree, suspend};
+use crate::HookContext;
 use crate::VElement;
 use crate::VNode;
 
+/// Renders the fiber `id` and returns the next fiber to work on.
+///
+/// Every visited fiber with an [EffectTag] is appended to `effects`, the list of changes
+/// applied by the next commit.
+/// A deleted fiber is not rendered, and the work continues after its subtree.
 pub(crate) fn perform_unit_of_work(
     id: FiberId,
     fiber_tree: &mut FiberTree,
     document: Option<&web_sys::Document>,
+    wip_root: &mut Option<FiberId>,
+    effects: &mut Vec<FiberId>,
+    rendered: &mut Vec<FiberId>,
 ) -> Option<FiberId> {
-    if fiber_tree
+    let effect_tag = fiber_tree
         .get(id)
-        .map(|fiber_node| matches!(fiber_node.node, Node::Component(Component { .. })))
-        .unwrap_or(false)
-    {
-        update_component_node(id, fiber_tree)
-    } else {
-        update_node(id, fiber_tree, document);
+        .and_then(|fiber_node| fiber_node.effect_tag.as_ref());
+    let deleted = matches!(effect_tag, Some(EffectTag::Deletion));
+    if effect_tag.is_some() {
+        effects.push(id);
+    }
+
+    if !deleted {
+        let restart_from = if fiber_tree
+            .get(id)
+            .map(|fiber_node| matches!(fiber_node.node, Node::Component(Component { .. })))
+            .unwrap_or(false)
+        {
+            update_component_node(id, fiber_tree)
+        } else {
+            update_node(id, fiber_tree, document)
+        };
+
+        if let Some(boundary_id) = restart_from {
+            if let Some(root_id) = *wip_root {
+                if fiber_tree.is_ancestor(boundary_id, root_id) {
+                    *wip_root = Some(boundary_id);
+                }
+            }
+
+            return Some(boundary_id);
+        }
     }
 
-    if let Some(child) = fiber_tree.get(id).and_then(|fiber_node| fiber_node.child) {
+    if let Some(child) = fiber_tree
+        .get(id)
+        .filter(|_| !deleted)
+        .and_then(|fiber_node| fiber_node.child)
+    {
         Some(child)
     } else {
         let mut next_fiber_id = Some(id);
         while let Some(some_next_fiber_id) = next_fiber_id {
             let fiber_node = fiber_tree.get(some_next_fiber_id);
+            // A fiber is complete once its subtree is, so the children are recorded before their parents
+            if !(deleted && some_next_fiber_id == id)
+                && matches!(
+                    fiber_node,
+                    Some(FiberNode {
+                        node: Node::Component(_),
+                        ..
+                    })
+                )
+            {
+                rendered.push(some_next_fiber_id);
+            }
+
+            // The work in progress ends with the subtree of its root
+            if Some(some_next_fiber_id) == *wip_root {
+                return None;
+            }
+
             if let Some(sibling) = fiber_node.and_then(|fiber_node| fiber_node.sibling) {
                 return Some(sibling);
             }
@@ -35,7 +87,7 @@ pub(crate) fn perform_unit_of_work(
     }
 }
 
-fn update_component_node(id: FiberId, fiber_tree: &mut FiberTree) {
+fn update_component_node(id: FiberId, fiber_tree: &mut FiberTree) -> Option<FiberId> {
     if let Some((
         effect_tag,
         Node::Component(Component {
@@ -54,11 +106,26 @@ fn update_component_node(id: FiberId, fiber_tree: &mut FiberTree) {
             };
 
         let elements = vec![function.render(&mut (id, hook_context))];
-        reconcile_children(id, elements, fiber_tree);
+        let suspended = hook_context.suspended;
+        let restart_from = reconcile_children(id, elements, fiber_tree);
+
+        let suspense_restart_from = if suspended {
+            suspend(id, fiber_tree)
+        } else {
+            resume(id, fiber_tree)
+        };
+
+        restart_from.or(suspense_restart_from)
+    } else {
+        None
     }
 }
 
-fn update_node(id: FiberId, fiber_tree: &mut FiberTree, document: Option<&web_sys::Document>) {
+fn update_node(
+    id: FiberId,
+    fiber_tree: &mut FiberTree,
+    document: Option<&web_sys::Document>,
+) -> Option<FiberId> {
     let element = fiber_tree.get_mut(id).unwrap();
     if match &element.node {
         Node::Element(Element { dom, .. }) => dom.is_none(),
@@ -78,10 +145,16 @@ fn update_node(id: FiberId, fiber_tree: &mut FiberTree, document: Option<&web_sy
         }
 
         reconcile_children(id, elements, fiber_tree)
+    } else {
+        None
     }
 }
 
-fn reconcile_children(id: FiberId, elements: Vec<VNode>, fiber_tree: &mut FiberTree) {
+fn reconcile_children(
+    id: FiberId,
+    elements: Vec<VNode>,
+    fiber_tree: &mut FiberTree,
+) -> Option<FiberId> {
     let wip_fiber_id = id;
     let mut current_id: Option<FiberId> = fiber_tree.get(wip_fiber_id).and_then(|node| node.child);
     let mut prev_sibling: Option<FiberId> = None;
@@ -92,17 +165,13 @@ fn reconcile_children(id: FiberId, elements: Vec<VNode>, fiber_tree: &mut FiberT
 
             match current_fiber {
                 Some(old_fiber) if old_fiber == &element => {
-                    match element {
+                    let update_data = match element {
                         VNode::Element(VElement {
                             attributes,
                             events,
                             children,
                             ..
                         }) => {
-                            old_fiber.effect_tag = Some(EffectTag::Update(UpdateData::Element {
-                                attributes,
-                                events,
-                            }));
                             if let Node::Element(Element {
                                 unprocessed_children,
                                 ..
@@ -110,15 +179,21 @@ fn reconcile_children(id: FiberId, elements: Vec<VNode>, fiber_tree: &mut FiberT
                             {
                                 *unprocessed_children = children;
                             }
+                            UpdateData::Element { attributes, events }
                         }
-                        VNode::Text(text) => {
-                            old_fiber.effect_tag = Some(EffectTag::Update(UpdateData::Text(text)));
-                        }
-                        VNode::Component(component) => {
-                            old_fiber.effect_tag =
-                                Some(EffectTag::Update(UpdateData::Component(component)))
-                        }
+                        VNode::Text(text) => UpdateData::Text(text),
+                        VNode::Component(component) => UpdateData::Component(component),
                     };
+                    // A fiber placed by a render that has not been committed yet
+                    // must keep its placement, so it's updated in place.
+                    if let Some(EffectTag::Placement) = old_fiber.effect_tag {
+                        old_fiber.node.update(update_data);
+                    } else if old_fiber.node.is_up_to_date(&update_data) {
+                        // Only the changed fibers end up in the effect list
+                        old_fiber.effect_tag = None;
+                    } else {
+                        old_fiber.effect_tag = Some(EffectTag::Update(update_data));
+                    }
                     old_fiber.parent = Some(wip_fiber_id);
 
                     None
@@ -143,13 +218,25 @@ fn reconcile_children(id: FiberId, elements: Vec<VNode>, fiber_tree: &mut FiberT
             .and_then(|fiber| fiber.sibling);
     }
 
+    let mut restart_from = None;
     while let Some(current) = current_id {
-        if let Some(old_fiber) = fiber_tree.get_mut(current) {
-            old_fiber.effect_tag = Some(EffectTag::Deletion);
-        }
-
         current_id = fiber_tree.get(current).and_then(|node| node.sibling);
+
+        restart_from = restart_from.or(resume_subtree(current, fiber_tree));
+        match fiber_tree.get_mut(current) {
+            // The fiber has never been committed so there is nothing to remove from the DOM
+            Some(old_fiber) if old_fiber.effect_tag == Some(EffectTag::Placement) => {
+                commit_effects(current, fiber_tree, HookContext::unmount);
+                fiber_tree.remove(current);
+            }
+            Some(old_fiber) => {
+                old_fiber.effect_tag = Some(EffectTag::Deletion);
+            }
+            None => {}
+        }
     }
+
+    restart_from
 }
 
 fn find_first_element_parent(node_id: FiberId, fiber_tree: &FiberTree) -> Option<FiberId> {
@@ -201,118 +288,167 @@ fn find_first_dom_child(node_id: FiberId, fiber_tree: &FiberTree) -> Option<Fibe
     None
 }
 
-pub(crate) fn commit(id: Option<FiberId>, fiber_tree: &mut FiberTree) {
-    if let Some(id) = id {
-        if let Some(parent_id) = find_first_element_parent(id, fiber_tree) {
-            let parent_dom =
-                fiber_tree
-                    .get(parent_id)
-                    .and_then(|parent_node| match &parent_node.node {
-                        Node::Element(Element { dom, .. }) => dom.clone(),
-                        _ => None,
-                    });
-
-            if let Some(effect_tag) = fiber_tree
-                .get_mut(id)
-                .and_then(|fiber_node| fiber_node.effect_tag.take())
-            {
-                match effect_tag {
-                    EffectTag::Placement => match (
-                        fiber_tree.get(id).map(|fiber_node| &fiber_node.node),
-                        parent_dom,
-                    ) {
-                        (Some(Node::Element(Element { dom: Some(dom), .. })), Some(parent_dom)) => {
+/// Commits the effect list collected during the render.
+///
+/// The deletions are applied first, then the placements and the updates, in the order of the list.
+pub(crate) fn commit(effects: &[FiberId], fiber_tree: &mut FiberTree) {
+    let (deletions, changes): (Vec<FiberId>, Vec<FiberId>) = effects.iter().partition(|id| {
+        matches!(
+            fiber_tree
+                .get(**id)
+                .and_then(|fiber_node| fiber_node.effect_tag.as_ref()),
+            Some(EffectTag::Deletion)
+        )
+    });
+
+    for id in deletions.into_iter().chain(changes) {
+        commit_fiber(id, fiber_tree);
+    }
+}
+
+fn commit_fiber(id: FiberId, fiber_tree: &mut FiberTree) {
+    let parent_dom = find_first_element_parent(id, fiber_tree)
+        .and_then(|parent_id| fiber_tree.get(parent_id))
+        .and_then(|parent_node| match &parent_node.node {
+            Node::Element(Element { dom, .. }) => dom.clone(),
+            _ => None,
+        });
+
+    if let Some(effect_tag) = fiber_tree
+        .get_mut(id)
+        .and_then(|fiber_node| fiber_node.effect_tag.take())
+    {
+        match effect_tag {
+            EffectTag::Placement => match (
+                fiber_tree.get(id).map(|fiber_node| &fiber_node.node),
+                parent_dom,
+            ) {
+                (Some(Node::Element(Element { dom: Some(dom), .. })), Some(parent_dom)) => {
+                    parent_dom
+                        .append_child(dom)
+                        .expect("append element child error");
+                }
+                (Some(Node::Text(Text { dom: Some(dom), .. })), Some(parent_dom)) => {
+                    parent_dom
+                        .append_child(dom)
+                        .expect("append text child error");
+                }
+                _ => {}
+            },
+            EffectTag::Update(update_data) => {
+                if let Some(fiber_node) = fiber_tree.get_mut(id) {
+                    fiber_node.node.update(update_data);
+                }
+            }
+            EffectTag::Deletion => {
+                match (fiber_tree.get(id), parent_dom) {
+                    (
+                        Some(FiberNode {
+                            node: Node::Element(Element { dom: Some(dom), .. }),
+                            ..
+                        }),
+                        Some(parent_dom),
+                    ) => {
+                        parent_dom
+                            .remove_child(dom)
+                            .expect("remove element child error");
+                    }
+                    (
+                        Some(FiberNode {
+                            node: Node::Text(Text { dom: Some(dom), .. }),
+                            ..
+                        }),
+                        Some(parent_dom),
+                    ) => {
+                        parent_dom
+                            .remove_child(dom)
+                            .expect("remove text child error");
+                    }
+                    (
+                        Some(FiberNode {
+                            node: Node::Component(_),
+                            ..
+                        }),
+                        Some(parent_dom),
+                    ) => match find_first_dom_child(id, fiber_tree)
+                        .and_then(|child_id| fiber_tree.get(child_id))
+                        .map(|child_node| &child_node.node)
+                    {
+                        Some(Node::Element(Element { dom: Some(dom), .. })) => {
                             parent_dom
-                                .append_child(dom)
-                                .expect("append element child error");
+                                .remove_child(dom)
+                                .expect("remove first component element child error");
                         }
-                        (Some(Node::Text(Text { dom: Some(dom), .. })), Some(parent_dom)) => {
+                        Some(Node::Text(Text { dom: Some(dom), .. })) => {
                             parent_dom
-                                .append_child(dom)
-                                .expect("append text child error");
+                                .remove_child(dom)
+                                .expect("remove first component text child error");
                         }
                         _ => {}
                     },
-                    EffectTag::Update(UpdateData::Element { attributes, events }) => {
-                        if let Some(Node::Element(element)) = fiber_tree
-                            .get_mut(id)
-                            .map(|fiber_node| &mut fiber_node.node)
-                        {
-                            element.update_element_dom(attributes, events)
-                        }
-                    }
-                    EffectTag::Update(UpdateData::Text(new_text)) => {
-                        if let Some(Node::Text(text)) = fiber_tree
-                            .get_mut(id)
-                            .map(|fiber_node| &mut fiber_node.node)
-                        {
-                            text.update_text_dom(new_text)
-                        }
-                    }
-                    EffectTag::Update(UpdateData::Component(new_component)) => {
-                        if let Some(Node::Component(Component { function, .. })) = fiber_tree
-                            .get_mut(id)
-                            .map(|fiber_node| &mut fiber_node.node)
-                        {
-                            *function = new_component;
-                        }
-                    }
-                    EffectTag::Deletion => {
-                        match (fiber_tree.get(id), parent_dom) {
-                            (
-                                Some(FiberNode {
-                                    node: Node::Element(Element { dom: Some(dom), .. }),
-                                    ..
-                                }),
-                                Some(parent_dom),
-                            ) => {
-                                parent_dom
-                                    .remove_child(dom)
-                                    .expect("remove element child error");
-                            }
-                            (
-                                Some(FiberNode {
-                                    node: Node::Text(Text { dom: Some(dom), .. }),
-                                    ..
-                                }),
-                                Some(parent_dom),
-                            ) => {
-                                parent_dom
-                                    .remove_child(dom)
-                                    .expect("remove text child error");
-                            }
-                            (
-                                Some(FiberNode {
-                                    node: Node::Component(_),
-                                    ..
-                                }),
-                                Some(parent_dom),
-                            ) => match find_first_dom_child(id, fiber_tree)
-                                .and_then(|child_id| fiber_tree.get(child_id))
-                                .map(|child_node| &child_node.node)
-                            {
-                                Some(Node::Element(Element { dom: Some(dom), .. })) => {
-                                    parent_dom
-                                        .remove_child(dom)
-                                        .expect("remove first component element child error");
-                                }
-                                Some(Node::Text(Text { dom: Some(dom), .. })) => {
-                                    parent_dom
-                                        .remove_child(dom)
-                                        .expect("remove first component text child error");
-                                }
-                                _ => {}
-                            },
-
-                            _ => {}
-                        }
-                        fiber_tree.remove(id);
-                    }
+
+                    _ => {}
                 }
+                commit_effects(id, fiber_tree, HookContext::unmount);
+                fiber_tree.remove(id);
+            }
+        }
+    }
+}
+
+/// Runs `run_effects` on the components in `ids`, skipping the ones removed from the tree
+pub(crate) fn run_component_effects(
+    ids: &[FiberId],
+    fiber_tree: &mut FiberTree,
+    run_effects: fn(&mut HookContext),
+) {
+    for id in ids {
+        if let Some(Node::Component(Component { hook_context, .. })) = fiber_tree
+            .get_mut(*id)
+            .map(|fiber_node| &mut fiber_node.node)
+        {
+            run_effects(hook_context);
+        }
+    }
+}
+
+pub(crate) fn commit_effects(
+    id: FiberId,
+    fiber_tree: &mut FiberTree,
+    run_effects: fn(&mut HookContext),
+) {
+    // Children run their effects before their parents
+    let mut current_id = id;
+    loop {
+        while let Some(child_id) = fiber_tree.get(current_id).and_then(|node| node.child) {
+            current_id = child_id;
+        }
+
+        loop {
+            if let Some(Node::Component(Component { hook_context, .. })) = fiber_tree
+                .get_mut(current_id)
+                .map(|fiber_node| &mut fiber_node.node)
+            {
+                run_effects(hook_context);
+            }
+
+            if current_id == id {
+                return;
+            }
+
+            let fiber_node = fiber_tree.get(current_id);
+            match (
+                fiber_node.and_then(|node| node.sibling),
+                fiber_node.and_then(|node| node.parent),
+            ) {
+                (Some(sibling_id), _) => {
+                    current_id = sibling_id;
+                    break;
+                }
+                (None, Some(parent_id)) => current_id = parent_id,
+                (None, None) => return,
             }
         }
-        commit(fiber_tree.get(id).and_then(|node| node.child), fiber_tree);
-        commit(fiber_tree.get(id).and_then(|node| node.sibling), fiber_tree);
     }
 }
 
@@ -347,6 +483,8 @@ mod tests {
             fiber_tree,
             next_unit_of_work: Some(root_id),
             wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
             document: None,
         }
     }
@@ -467,24 +605,20 @@ mod tests {
     fn manually_generate_working_context(app: &mut App, vdom: VNode, root: FiberId) {
         app.wip_root = Some(root);
         app.next_unit_of_work = Some(root);
-        app.fiber_tree
-            .get_mut(root)
-            .map(|node| match &mut node.node {
-                Node::Element(Element {
-                    unprocessed_children,
-                    ..
-                }) => {
-                    *unprocessed_children = vec![vdom];
-                }
-                _ => {}
-            });
+        if let Some(Node::Element(Element {
+            unprocessed_children,
+            ..
+        })) = app.fiber_tree.get_mut(root).map(|node| &mut node.node)
+        {
+            *unprocessed_children = vec![vdom];
+        }
     }
 
     #[test]
     fn simple_vdom_creation() {
         let vdom = || Div.with_child("hello world").into();
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -501,7 +635,7 @@ mod tests {
                 .into()
         };
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -515,7 +649,7 @@ mod tests {
     fn change_text() {
         let vdom = || "hello world".into();
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -540,7 +674,7 @@ mod tests {
                 .into()
         };
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -566,7 +700,7 @@ mod tests {
                 .into()
         };
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -604,7 +738,7 @@ mod tests {
                 .into()
         };
         let mut app = create_app(vdom());
-        let root = app.wip_root.unwrap_or(0);
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
 
         work_on_dom(&mut app);
         commit(&mut app);
@@ -623,4 +757,131 @@ mod tests {
 
         compare_vdom_with_dom(vdom(), &mut app, root);
     }
+
+    #[test]
+    fn commit_long_list() {
+        let vdom = |text: &str| {
+            Div.with_children((0..50_000).map(|_| Div.with_child(text).into()).collect())
+                .into()
+        };
+        let mut app = create_app(vdom("row"));
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
+
+        work_on_dom(&mut app);
+        commit(&mut app);
+
+        compare_vdom_with_dom(vdom("row"), &mut app, root);
+
+        manually_generate_working_context(&mut app, vdom("updated row"), root);
+
+        work_on_dom(&mut app);
+        commit(&mut app);
+
+        compare_vdom_with_dom(vdom("updated row"), &mut app, root);
+    }
+
+    #[test]
+    fn effect_list_contains_only_changes() {
+        let vdom = |changed_row: usize| {
+            Div.with_children(
+                (0..1_000)
+                    .map(|row| {
+                        let text = if row == changed_row { "changed" } else { "row" };
+                        Div.with_child(text).into()
+                    })
+                    .collect(),
+            )
+            .into()
+        };
+        let mut app = create_app(vdom(usize::MAX));
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
+
+        work_on_dom(&mut app);
+        commit(&mut app);
+
+        manually_generate_working_context(&mut app, vdom(500), root);
+        work_on_dom(&mut app);
+
+        assert_eq!(app.effects.len(), 1);
+        assert!(matches!(
+            app.fiber_tree
+                .get(app.effects[0])
+                .map(|fiber| &fiber.effect_tag),
+            Some(Some(EffectTag::Update(UpdateData::Text(_))))
+        ));
+
+        commit(&mut app);
+        assert!(app.effects.is_empty());
+
+        compare_vdom_with_dom(vdom(500), &mut app, root);
+    }
+
+    #[component(Plain)]
+    fn plain() -> VNode {
+        "plain".into()
+    }
+
+    #[test]
+    fn update_keeps_following_siblings() {
+        let vdom = || {
+            Div.with_child(Plain(()))
+                .with_child(Div.with_child("sibling"))
+                .into()
+        };
+        let mut app = create_app(vdom());
+        let root = app.wip_root.unwrap_or_else(|| 0.into());
+
+        work_on_dom(&mut app);
+        commit(&mut app);
+
+        let plain = app
+            .fiber_tree
+            .get(root)
+            .and_then(|div| div.child)
+            .and_then(|div| app.fiber_tree.get(div))
+            .and_then(|div| div.child)
+            .unwrap();
+        app.wip_root = Some(plain);
+        app.next_unit_of_work = Some(plain);
+
+        work_on_dom(&mut app);
+        commit(&mut app);
+
+        compare_vdom_with_dom(vdom(), &mut app, root);
+    }
+
+    thread_local! {
+        static EFFECT_CALLS: std::cell::RefCell<Vec<&'static str>> = std::cell::RefCell::new(Vec::new());
+    }
+
+    #[component(EffectComponent)]
+    fn effect_component() -> VNode {
+        use_effect(
+            || {
+                EFFECT_CALLS.with(|calls| calls.borrow_mut().push("effect"));
+                None::<fn()>
+            },
+            Some(()),
+        );
+        use_layout_effect(
+            || {
+                EFFECT_CALLS.with(|calls| calls.borrow_mut().push("layout"));
+                None::<fn()>
+            },
+            Some(()),
+        );
+
+        Div.into()
+    }
+
+    #[test]
+    fn effects_run_after_commit() {
+        let mut app = create_app(EffectComponent(()).into());
+
+        work_on_dom(&mut app);
+        EFFECT_CALLS.with(|calls| assert!(calls.borrow().is_empty()));
+
+        commit(&mut app);
+        EFFECT_CALLS.with(|calls| assert_eq!(*calls.borrow(), vec!["layout", "effect"]));
+    }
 }
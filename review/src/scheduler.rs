@@ -0,0 +1,269 @@
+use crate::app::App;
+use crate::fiber::{FiberId, FiberTree};
+use crate::hooks::clear_pass_snapshots;
+use crate::node::Node;
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+
+/// The fibers waiting to be rendered again, in the order they were scheduled
+#[derive(Debug, Default)]
+struct UpdateQueue {
+    order: VecDeque<FiberId>,
+    /// The fibers in `order`, so a fiber is queued only once without scanning the queue
+    queued: HashSet<FiberId>,
+}
+
+impl UpdateQueue {
+    fn push(&mut self, fiber_id: FiberId) {
+        if self.queued.insert(fiber_id) {
+            self.order.push_back(fiber_id);
+        }
+    }
+
+    fn pop(&mut self) -> Option<FiberId> {
+        let fiber_id = self.order.pop_front()?;
+        self.queued.remove(&fiber_id);
+
+        Some(fiber_id)
+    }
+
+    fn take(&mut self) -> VecDeque<FiberId> {
+        self.queued.clear();
+        std::mem::take(&mut self.order)
+    }
+}
+
+thread_local! {
+    static UPDATE_QUEUE: RefCell<UpdateQueue> = RefCell::new(UpdateQueue::default());
+}
+
+/// Enqueues a re-render of the fiber.
+///
+/// The queue lives outside of the [App] so updates can be scheduled while the app is borrowed,
+/// for example from an effect or during a render.
+pub(crate) fn schedule_update(fiber_id: FiberId) {
+    UPDATE_QUEUE.with(|queue| queue.borrow_mut().push(fiber_id));
+}
+
+#[cfg(test)]
+pub(crate) fn take_scheduled_updates() -> Vec<FiberId> {
+    UPDATE_QUEUE.with(|queue| queue.borrow_mut().take().into())
+}
+
+/// Starts the next update in the queue when there is no work in progress.
+///
+/// When there is a work in progress, the queued updates that it can include restart it instead.
+pub(crate) fn start_next_update(app: &mut App) {
+    if let Some(wip_root) = app.wip_root {
+        restart_work(app, wip_root);
+        return;
+    }
+
+    while let Some(fiber_id) = UPDATE_QUEUE.with(|queue| queue.borrow_mut().pop()) {
+        if app.fiber_tree.get(fiber_id).is_some() {
+            app.wip_root = Some(fiber_id);
+            app.next_unit_of_work = Some(fiber_id);
+            return;
+        }
+
+        warn_unmounted(fiber_id);
+    }
+}
+
+/// Restarts the work in progress from a component that contains both its root
+/// and the updated fibers, so the next commit doesn't show stale states.
+///
+/// The fibers already rendered are rendered again on top of their uncommitted changes,
+/// which are reconciled like any other change.
+/// The updates outside of every component containing the work in progress stay in the queue.
+fn restart_work(app: &mut App, wip_root: FiberId) {
+    let queue = UPDATE_QUEUE.with(|queue| queue.borrow_mut().take());
+
+    let mut restart_from = None;
+    let mut remaining = Vec::with_capacity(queue.len());
+    for fiber_id in queue {
+        if app.fiber_tree.get(fiber_id).is_none() {
+            warn_unmounted(fiber_id);
+            continue;
+        }
+
+        match find_restart_root(&app.fiber_tree, restart_from.unwrap_or(wip_root), fiber_id) {
+            Some(root_id) => restart_from = Some(root_id),
+            None => remaining.push(fiber_id),
+        }
+    }
+
+    UPDATE_QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        // Updates scheduled meanwhile are kept after the older ones
+        let scheduled = queue.take();
+        for fiber_id in remaining.into_iter().chain(scheduled) {
+            queue.push(fiber_id);
+        }
+    });
+
+    if let Some(root_id) = restart_from {
+        app.wip_root = Some(root_id);
+        app.next_unit_of_work = Some(root_id);
+        // The new root contains the old one, so every change is collected again
+        app.effects.clear();
+        app.rendered.clear();
+        clear_pass_snapshots();
+    }
+}
+
+/// The closest component containing both `wip_root` and `fiber_id`.
+///
+/// Only a component can be rendered again from its root,
+/// because an element doesn't keep its children once they are reconciled.
+fn find_restart_root(
+    fiber_tree: &FiberTree,
+    wip_root: FiberId,
+    fiber_id: FiberId,
+) -> Option<FiberId> {
+    let mut ancestor_id = Some(wip_root);
+    while let Some(some_ancestor_id) = ancestor_id {
+        let ancestor = fiber_tree.get(some_ancestor_id)?;
+        if matches!(ancestor.node, Node::Component(_))
+            && (some_ancestor_id == fiber_id || fiber_tree.is_ancestor(some_ancestor_id, fiber_id))
+        {
+            return Some(some_ancestor_id);
+        }
+
+        ancestor_id = ancestor.parent;
+    }
+
+    None
+}
+
+fn warn_unmounted(_fiber_id: FiberId) {
+    #[cfg(debug_assertions)]
+    log::warn!(
+        "ignoring an update scheduled on the unmounted fiber {:?}",
+        _fiber_id
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{commit_work, work_loop};
+    use crate::node::Element;
+    use crate::{Events, Tag, VNode};
+    use std::collections::HashMap;
+
+    fn mount(children: Vec<VNode>) -> (App, FiberId) {
+        let mut fiber_tree = FiberTree::default();
+        let root_id = fiber_tree.new_node(Node::Element(Element {
+            dom: None,
+            tag: Tag::Empty,
+            attributes: HashMap::with_capacity(0),
+            events: Events(HashMap::with_capacity(0)),
+            unprocessed_children: children,
+        }));
+        let mut app = App {
+            fiber_tree,
+            next_unit_of_work: Some(root_id),
+            wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
+            document: None,
+        };
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        (app, root_id)
+    }
+
+    #[test]
+    fn queue_skips_duplicates() {
+        let mut queue = UpdateQueue::default();
+        let first_id = FiberId::from(1);
+        let second_id = FiberId::from(2);
+
+        queue.push(first_id);
+        queue.push(second_id);
+        queue.push(first_id);
+        assert_eq!(queue.pop(), Some(first_id));
+
+        // A fiber can be queued again once its update is taken
+        queue.push(first_id);
+        assert_eq!(queue.take(), vec![second_id, first_id]);
+        assert_eq!(queue.pop(), None);
+
+        queue.push(second_id);
+        assert_eq!(queue.pop(), Some(second_id));
+    }
+
+    #[test]
+    fn queued_updates_run_in_order() {
+        let (mut app, root_id) = mount(vec!["first".into(), "second".into()]);
+
+        let first_id = app.fiber_tree.get(root_id).and_then(|n| n.child).unwrap();
+        let second_id = app
+            .fiber_tree
+            .get(first_id)
+            .and_then(|n| n.sibling)
+            .unwrap();
+
+        schedule_update(first_id);
+        schedule_update(second_id);
+        schedule_update(first_id);
+
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, Some(first_id));
+
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, Some(first_id));
+
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, Some(second_id));
+
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, None);
+    }
+
+    #[test]
+    fn next_update_after_a_render_without_children() {
+        let (mut app, root_id) = mount(Vec::new());
+
+        // The commit ends the work even if the root has nothing to show,
+        // otherwise the queued updates would never start
+        assert_eq!(app.wip_root, None);
+
+        schedule_update(root_id);
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, Some(root_id));
+
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+        assert_eq!(app.wip_root, None);
+    }
+
+    #[test]
+    fn skip_updates_of_removed_fibers() {
+        let (mut app, root_id) = mount(vec!["removed".into()]);
+
+        let removed_id = app.fiber_tree.get(root_id).and_then(|n| n.child).unwrap();
+        app.fiber_tree.remove(removed_id);
+
+        schedule_update(removed_id);
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, None);
+
+        app.fiber_tree.new_node(Node::Element(Element {
+            dom: None,
+            tag: Tag::Empty,
+            attributes: HashMap::with_capacity(0),
+            events: Events(HashMap::with_capacity(0)),
+            unprocessed_children: vec![],
+        }));
+        schedule_update(removed_id);
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, None);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::{Hook, HookBuilder, HookContext};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+struct SignalState<T> {
+    value: Rc<T>,
+    subscribers: Vec<FiberId>,
+}
+
+/// A reactive value that can be shared between components.
+///
+/// A signal can be created anywhere, also outside of components, and cloned to share it.
+/// Components read it with the [use_signal] hook, and only the components that read it
+/// are rendered again when it's written.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, use_signal, Signal, ElementBuilder, callback, children};
+/// # use review::EventType::OnClick;
+/// # use review::Tag::{Button, Div, P};
+/// thread_local! {
+///     static COUNTER: Signal<u32> = Signal::new(0);
+/// }
+///
+/// #[component(Counter)]
+/// pub fn counter() -> VNode {
+///     let signal = COUNTER.with(Signal::clone);
+///     let counter = use_signal(&signal);
+///
+///     P.with_child(format!("Current value {}", *counter)).into()
+/// }
+///
+/// #[component(Increase)]
+/// pub fn increase() -> VNode {
+///     Button
+///         .with_child("Increase counter")
+///         .with_event(
+///             OnClick,
+///             callback!(|| COUNTER.with(|counter| counter.update(|prev| prev + 1))),
+///         )
+///         .into()
+/// }
+///
+/// #[component(App)]
+/// pub fn app() -> VNode {
+///     Div.with_children(children!(Counter(()), Increase(()))).into()
+/// }
+/// ```
+pub struct Signal<T> {
+    state: Rc<RefCell<SignalState<T>>>,
+}
+
+impl<T> Signal<T> {
+    /// Creates a signal with the initial `value`
+    pub fn new(value: T) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(SignalState {
+                value: Rc::new(value),
+                subscribers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns the current value without subscribing to the signal
+    pub fn get(&self) -> Rc<T> {
+        self.state.borrow().value.clone()
+    }
+
+    /// Sets a new value and schedules a re-render of every subscribed component
+    pub fn set(&self, value: T) {
+        let subscribers = {
+            let mut state = self.state.borrow_mut();
+            state.value = Rc::new(value);
+            state.subscribers.clone()
+        };
+
+        for fiber_id in subscribers {
+            schedule_update(fiber_id);
+        }
+    }
+
+    /// Sets a new value computed from the current one
+    pub fn update<F: FnOnce(&T) -> T>(&self, update: F) {
+        let value = update(&self.get());
+        self.set(value);
+    }
+
+    fn subscribe(&self, fiber_id: FiberId) {
+        let mut state = self.state.borrow_mut();
+        if !state.subscribers.contains(&fiber_id) {
+            state.subscribers.push(fiber_id);
+        }
+    }
+
+    fn unsubscribe(&self, fiber_id: FiberId) {
+        self.state
+            .borrow_mut()
+            .subscribers
+            .retain(|subscriber| *subscriber != fiber_id);
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Signal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signal")
+            .field("value", &self.state.borrow().value)
+            .finish()
+    }
+}
+
+pub struct UseSignalBuilder<T> {
+    signal: Signal<T>,
+}
+
+/// This hook reads a [Signal] and subscribes the component to it.
+///
+/// Returns the current value of the signal.
+/// The component is rendered again every time the signal is written,
+/// and it's unsubscribed when it's removed from the tree.
+pub fn use_signal<T>(signal: &Signal<T>) -> UseSignalBuilder<T> {
+    UseSignalBuilder {
+        signal: signal.clone(),
+    }
+}
+
+struct SignalHook<T> {
+    signal: Signal<T>,
+    fiber_id: FiberId,
+}
+
+impl<T> Debug for SignalHook<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("SignalHook")
+            .field("fiber_id", &self.fiber_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> Hook for SignalHook<T> {
+    fn unmount(&mut self) {
+        self.signal.unsubscribe(self.fiber_id);
+    }
+}
+
+impl<T: 'static> HookBuilder<Rc<T>> for UseSignalBuilder<T> {
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> Rc<T> {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            self.signal.subscribe(*fiber_id);
+            hook_context.hooks.push(Box::new(SignalHook {
+                signal: self.signal.clone(),
+                fiber_id: *fiber_id,
+            }));
+        }
+
+        let hook: &mut SignalHook<T> = hook_context.get_mut_hook(hook_position);
+        if !hook.signal.ptr_eq(&self.signal) {
+            hook.unmount();
+            self.signal.subscribe(*fiber_id);
+            hook.signal = self.signal.clone();
+        }
+
+        self.signal.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscribers<T>(signal: &Signal<T>) -> Vec<FiberId> {
+        signal.state.borrow().subscribers.clone()
+    }
+
+    #[test]
+    fn only_readers_are_subscribed() {
+        let signal = Signal::new(1);
+        let mut reader = HookContext::default();
+        let mut other = HookContext::default();
+
+        let value = reader.render_hook(3.into(), use_signal(&signal));
+        assert_eq!(*value, 1);
+        other.render_hook(4.into(), use_signal(&Signal::new(0)));
+
+        assert_eq!(subscribers(&signal), vec![FiberId::from(3)]);
+
+        signal.update(|prev| prev + 1);
+        let value = reader.render_hook(3.into(), use_signal(&signal));
+
+        assert_eq!(*value, 2);
+        assert_eq!(subscribers(&signal), vec![FiberId::from(3)]);
+    }
+
+    #[test]
+    fn unsubscribe_on_unmount() {
+        let signal = Signal::new("signal");
+        let mut context = HookContext::default();
+
+        use_signal(&signal).build(&mut (0.into(), &mut context));
+        assert_eq!(subscribers(&signal), vec![FiberId::from(0)]);
+
+        context.unmount();
+        assert!(subscribers(&signal).is_empty());
+    }
+
+    #[test]
+    fn resubscribe_when_signal_changes() {
+        let old_signal = Signal::new(1);
+        let new_signal = Signal::new(2);
+        let mut context = HookContext::default();
+
+        context.render_hook(0.into(), use_signal(&old_signal));
+        let value = context.render_hook(0.into(), use_signal(&new_signal));
+
+        assert_eq!(*value, 2);
+        assert!(subscribers(&old_signal).is_empty());
+        assert_eq!(subscribers(&new_signal), vec![FiberId::from(0)]);
+    }
+}
@@ -0,0 +1,421 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::{Hook, HookBuilder, HookContext};
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+/// A middleware intercepts the actions dispatched to a [Store].
+///
+/// It receives the store, the action and the `next` function that sends the action to the
+/// following middleware and finally to the reducer.
+/// A middleware can change the action, dispatch other actions or skip `next` to drop it.
+///
+/// It's implemented for every `Fn(&Store<S, A>, A, &dyn Fn(A))`.
+pub trait Middleware<S, A> {
+    fn dispatch(&self, store: &Store<S, A>, action: A, next: &dyn Fn(A));
+}
+
+impl<S, A, F: Fn(&Store<S, A>, A, &dyn Fn(A))> Middleware<S, A> for F {
+    fn dispatch(&self, store: &Store<S, A>, action: A, next: &dyn Fn(A)) {
+        self(store, action, next)
+    }
+}
+
+/// A [Middleware] that logs every action and the resulting state
+pub fn logger<S: Debug, A: Debug>() -> impl Middleware<S, A> {
+    |store: &Store<S, A>, action: A, next: &dyn Fn(A)| {
+        log::debug!("dispatching action {:?}", action);
+        next(action);
+        log::debug!("new state {:?}", store.state());
+    }
+}
+
+/// A [Middleware] that calls `save` with the new state after every action,
+/// for example to write it to the local storage
+pub fn persist<S, A, F: Fn(&S)>(save: F) -> impl Middleware<S, A> {
+    move |store: &Store<S, A>, action: A, next: &dyn Fn(A)| {
+        next(action);
+        save(&store.state());
+    }
+}
+
+type Reducer<S, A> = Box<dyn Fn(&S, A) -> S>;
+
+struct Subscription<S> {
+    id: usize,
+    fiber_id: FiberId,
+    changed: Box<dyn Fn(&S) -> bool>,
+}
+
+struct StoreState<S, A> {
+    state: RefCell<Rc<S>>,
+    reducer: Reducer<S, A>,
+    middlewares: Vec<Box<dyn Middleware<S, A>>>,
+    subscriptions: RefCell<Vec<Subscription<S>>>,
+    next_subscription_id: Cell<usize>,
+}
+
+/// A container for app-wide state updated by a reducer.
+///
+/// The state is changed only by dispatching actions, which go through the middlewares
+/// and are then applied by the reducer.
+/// A store can be created anywhere, also outside of components, and cloned to share it,
+/// so actions can be dispatched for example from a websocket handler.
+///
+/// Components read the state with the [use_selector] hook.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, logger, use_selector, Store, ElementBuilder, callback, children};
+/// # use review::EventType::OnClick;
+/// # use review::Tag::{Button, Div, P};
+/// #[derive(Debug, Default)]
+/// pub struct AppState {
+///     cart: Vec<String>,
+/// }
+///
+/// #[derive(Debug)]
+/// pub enum Action {
+///     AddToCart(String),
+/// }
+///
+/// fn reducer(state: &AppState, action: Action) -> AppState {
+///     match action {
+///         Action::AddToCart(item) => {
+///             let mut cart = state.cart.clone();
+///             cart.push(item);
+///             AppState { cart }
+///         }
+///     }
+/// }
+///
+/// thread_local! {
+///     static STORE: Store<AppState, Action> =
+///         Store::new(AppState::default(), reducer).with_middleware(logger());
+/// }
+///
+/// #[component(Cart)]
+/// pub fn cart() -> VNode {
+///     let store = STORE.with(Store::clone);
+///     let items = use_selector(&store, |state| state.cart.len());
+///
+///     Div.with_children(children!(
+///         P.with_child(format!("{} items", items)),
+///         Button.with_child("Add").with_event(
+///             OnClick,
+///             callback!(|| STORE.with(|store| store.dispatch(Action::AddToCart("book".to_owned()))))
+///         )
+///     ))
+///     .into()
+/// }
+/// ```
+pub struct Store<S, A> {
+    inner: Rc<StoreState<S, A>>,
+}
+
+impl<S, A> Store<S, A> {
+    /// Creates a store with the `initial` state and the `reducer` that applies the actions
+    pub fn new<R: Fn(&S, A) -> S + 'static>(initial: S, reducer: R) -> Self {
+        Self {
+            inner: Rc::new(StoreState {
+                state: RefCell::new(Rc::new(initial)),
+                reducer: Box::new(reducer),
+                middlewares: Vec::new(),
+                subscriptions: RefCell::new(Vec::new()),
+                next_subscription_id: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Adds a [Middleware] to the store.
+    ///
+    /// The middlewares are called in the order they are added.
+    ///
+    /// # Panics
+    /// Panics if the store was already cloned.
+    pub fn with_middleware<M: Middleware<S, A> + 'static>(mut self, middleware: M) -> Self {
+        Rc::get_mut(&mut self.inner)
+            .expect("middlewares must be added before the store is shared")
+            .middlewares
+            .push(Box::new(middleware));
+
+        self
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> Rc<S> {
+        self.inner.state.borrow().clone()
+    }
+
+    /// Dispatches an action to the middlewares and then to the reducer.
+    ///
+    /// The components whose selected state changed are rendered again.
+    pub fn dispatch(&self, action: A) {
+        self.dispatch_from(0, action);
+    }
+
+    fn dispatch_from(&self, index: usize, action: A) {
+        match self.inner.middlewares.get(index) {
+            Some(middleware) => middleware.dispatch(self, action, &|action| {
+                self.dispatch_from(index + 1, action)
+            }),
+            None => self.reduce(action),
+        }
+    }
+
+    fn reduce(&self, action: A) {
+        let state = Rc::new((self.inner.reducer)(&self.state(), action));
+        self.inner.state.replace(state.clone());
+
+        let changed_fibers: Vec<FiberId> = self
+            .inner
+            .subscriptions
+            .borrow()
+            .iter()
+            .filter(|subscription| (subscription.changed)(&state))
+            .map(|subscription| subscription.fiber_id)
+            .collect();
+
+        for fiber_id in changed_fibers {
+            schedule_update(fiber_id);
+        }
+    }
+
+    fn subscribe(&self, subscription_id: Option<usize>, subscription: Subscription<S>) -> usize {
+        let mut subscriptions = self.inner.subscriptions.borrow_mut();
+        if let Some(existing) = subscription_id.and_then(|id| {
+            subscriptions
+                .iter_mut()
+                .find(|subscription| subscription.id == id)
+        }) {
+            existing.changed = subscription.changed;
+            return existing.id;
+        }
+
+        let id = self.inner.next_subscription_id.get();
+        self.inner.next_subscription_id.set(id + 1);
+        subscriptions.push(Subscription { id, ..subscription });
+
+        id
+    }
+
+    fn unsubscribe(&self, subscription_id: usize) {
+        self.inner
+            .subscriptions
+            .borrow_mut()
+            .retain(|subscription| subscription.id != subscription_id);
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl<S, A> Clone for Store<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S: Debug, A> Debug for Store<S, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+pub struct UseSelectorBuilder<S, A, F> {
+    store: Store<S, A>,
+    selector: F,
+}
+
+/// This hook selects a slice of the state of a [Store] and subscribes the component to it.
+///
+/// Returns the slice returned by `selector`.
+/// After an action is dispatched the component is rendered again only if the selected slice
+/// changed, and it's unsubscribed when it's removed from the tree.
+///
+/// > Note
+/// >
+/// > The selected slice must implement `PartialEq` and `Clone`
+pub fn use_selector<S, A, F, T>(store: &Store<S, A>, selector: F) -> UseSelectorBuilder<S, A, F>
+where
+    F: Fn(&S) -> T + 'static,
+    T: PartialEq + Clone + 'static,
+{
+    UseSelectorBuilder {
+        store: store.clone(),
+        selector,
+    }
+}
+
+struct SelectorHook<S, A> {
+    store: Store<S, A>,
+    subscription_id: Option<usize>,
+}
+
+impl<S, A> Debug for SelectorHook<S, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("SelectorHook")
+            .field("subscription_id", &self.subscription_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: 'static, A: 'static> Hook for SelectorHook<S, A> {
+    fn unmount(&mut self) {
+        if let Some(subscription_id) = self.subscription_id.take() {
+            self.store.unsubscribe(subscription_id);
+        }
+    }
+}
+
+impl<S, A, F, T> HookBuilder<T> for UseSelectorBuilder<S, A, F>
+where
+    S: 'static,
+    A: 'static,
+    F: Fn(&S) -> T + 'static,
+    T: PartialEq + Clone + 'static,
+{
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> T {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            hook_context.hooks.push(Box::new(SelectorHook {
+                store: self.store.clone(),
+                subscription_id: None,
+            }));
+        }
+
+        let hook: &mut SelectorHook<S, A> = hook_context.get_mut_hook(hook_position);
+        if !hook.store.ptr_eq(&self.store) {
+            hook.unmount();
+            hook.store = self.store.clone();
+        }
+
+        let selector = self.selector;
+        let selected = selector(&self.store.state());
+        let rendered = selected.clone();
+        hook.subscription_id = Some(self.store.subscribe(
+            hook.subscription_id,
+            Subscription {
+                id: 0,
+                fiber_id: *fiber_id,
+                changed: Box::new(move |state| selector(state) != rendered),
+            },
+        ));
+
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::take_scheduled_updates;
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct State {
+        count: u32,
+        name: String,
+    }
+
+    enum Action {
+        Increase,
+        Rename(&'static str),
+    }
+
+    fn reducer(state: &State, action: Action) -> State {
+        match action {
+            Action::Increase => State {
+                count: state.count + 1,
+                ..state.clone()
+            },
+            Action::Rename(name) => State {
+                name: name.to_owned(),
+                ..state.clone()
+            },
+        }
+    }
+
+    fn count(state: &State) -> u32 {
+        state.count
+    }
+
+    fn name(state: &State) -> String {
+        state.name.clone()
+    }
+
+    #[test]
+    fn render_only_when_selection_changes() {
+        let store = Store::new(State::default(), reducer);
+        let mut count_context = HookContext::default();
+        let mut name_context = HookContext::default();
+
+        let selected = count_context.render_hook(1.into(), use_selector(&store, count));
+        name_context.render_hook(2.into(), use_selector(&store, name));
+        assert_eq!(selected, 0);
+
+        take_scheduled_updates();
+        store.dispatch(Action::Increase);
+        assert_eq!(take_scheduled_updates(), vec![FiberId::from(1)]);
+
+        let selected = count_context.render_hook(1.into(), use_selector(&store, count));
+        assert_eq!(selected, 1);
+
+        store.dispatch(Action::Rename("reView"));
+        assert_eq!(take_scheduled_updates(), vec![FiberId::from(2)]);
+
+        name_context.render_hook(2.into(), use_selector(&store, name));
+
+        store.dispatch(Action::Rename("reView"));
+        assert!(take_scheduled_updates().is_empty());
+    }
+
+    #[test]
+    fn middlewares_run_in_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (first, second) = (calls.clone(), calls.clone());
+        let store = Store::new(State::default(), reducer)
+            .with_middleware(
+                move |_: &Store<State, Action>, action, next: &dyn Fn(Action)| {
+                    first.borrow_mut().push("first");
+                    next(action);
+                },
+            )
+            .with_middleware(move |_: &Store<State, Action>, _, next: &dyn Fn(Action)| {
+                second.borrow_mut().push("second");
+                next(Action::Rename("changed"));
+            })
+            .with_middleware(persist({
+                let calls = calls.clone();
+                move |state: &State| {
+                    assert_eq!(state.name, "changed");
+                    calls.borrow_mut().push("persist");
+                }
+            }));
+
+        store.dispatch(Action::Increase);
+
+        assert_eq!(*calls.borrow(), vec!["first", "second", "persist"]);
+        assert_eq!(store.state().count, 0);
+    }
+
+    #[test]
+    fn unsubscribe_on_unmount() {
+        let store = Store::new(State::default(), reducer);
+        let mut context = HookContext::default();
+
+        use_selector(&store, |state| state.count).build(&mut (0.into(), &mut context));
+        context.unmount();
+
+        take_scheduled_updates();
+        store.dispatch(Action::Increase);
+        assert!(take_scheduled_updates().is_empty());
+    }
+}
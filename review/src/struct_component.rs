@@ -0,0 +1,310 @@
+use crate::fiber::FiberId;
+use crate::scheduler::schedule_update;
+use crate::{Callback, ComponentProvider, Hook, HookBuilder, HookContext, VNode};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Whether a [StructComponent] must be rendered again
+pub type ShouldRender = bool;
+
+/// A component written as a state machine instead of a function with hooks.
+///
+/// The component is created with its first props, it changes its state in `update` when it
+/// receives a message from its [Link], and it's rendered with `view`.
+/// It becomes a [VNode] with a [StructNode], so it can be mixed with function components.
+///
+/// # Example
+/// ```rust
+/// # use review::{html, Link, ShouldRender, StructComponent, StructNode, VNode};
+/// pub enum Msg {
+///     Increment,
+///     Reset,
+/// }
+///
+/// pub struct Counter {
+///     value: u32,
+/// }
+///
+/// impl StructComponent for Counter {
+///     type Message = Msg;
+///     type Properties = u32;
+///
+///     fn create(initial: &u32) -> Self {
+///         Self { value: *initial }
+///     }
+///
+///     fn update(&mut self, msg: Msg) -> ShouldRender {
+///         match msg {
+///             Msg::Increment => self.value += 1,
+///             Msg::Reset => self.value = 0,
+///         }
+///
+///         true
+///     }
+///
+///     fn view(&self, link: &Link<Self>) -> VNode {
+///         html! {
+///             <div>
+///                 {self.value.to_string()}
+///                 <button on_click={link.callback(|_: ()| Msg::Increment)}>"+"</button>
+///                 <button on_click={link.callback(|_: ()| Msg::Reset)}>"Reset"</button>
+///             </div>
+///         }
+///     }
+/// }
+///
+/// let counter: VNode = StructNode::<Counter>::new(5).into();
+/// ```
+pub trait StructComponent: Sized + 'static {
+    /// The messages that change the state of the component
+    type Message: 'static;
+
+    /// The props of the component, `changed` is called when they differ from the previous ones
+    type Properties: PartialEq + Clone + 'static;
+
+    /// Creates the component the first time it's rendered
+    fn create(props: &Self::Properties) -> Self;
+
+    /// Updates the state with a message sent with a [Link]
+    fn update(&mut self, msg: Self::Message) -> ShouldRender;
+
+    /// Called when the component is rendered again with different props
+    fn changed(&mut self, _props: &Self::Properties) -> ShouldRender {
+        true
+    }
+
+    /// Renders the component
+    fn view(&self, link: &Link<Self>) -> VNode;
+}
+
+/// A handle that sends messages to a [StructComponent] and schedules its render
+pub struct Link<C: StructComponent> {
+    messages: Rc<RefCell<VecDeque<C::Message>>>,
+    fiber_id: FiberId,
+}
+
+impl<C: StructComponent> Link<C> {
+    /// Sends a message to the component, that handles it in `update` in its next render
+    pub fn send_message(&self, msg: impl Into<C::Message>) {
+        self.messages.borrow_mut().push_back(msg.into());
+
+        schedule_update(self.fiber_id);
+    }
+
+    /// Creates a [Callback] that sends the message returned by `function`
+    pub fn callback<IN, F>(&self, function: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> C::Message + 'static,
+    {
+        let link = self.clone();
+
+        Callback::from(move |value| link.send_message(function(value)))
+    }
+}
+
+impl<C: StructComponent> Clone for Link<C> {
+    fn clone(&self) -> Self {
+        Self {
+            messages: self.messages.clone(),
+            fiber_id: self.fiber_id,
+        }
+    }
+}
+
+impl<C: StructComponent> Debug for Link<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Link")
+            .field("fiber_id", &self.fiber_id)
+            .finish()
+    }
+}
+
+/// A [StructComponent] with its props, that can become a [VNode]
+pub struct StructNode<C: StructComponent>(pub C::Properties);
+
+impl<C: StructComponent> StructNode<C> {
+    /// Creates the node of the component with its props
+    pub fn new(props: C::Properties) -> Self {
+        Self(props)
+    }
+}
+
+impl<C: StructComponent> Debug for StructNode<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(std::any::type_name::<C>()).finish()
+    }
+}
+
+impl<C: StructComponent> ComponentProvider for StructNode<C> {
+    type Props = C::Properties;
+
+    fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+        UseStructComponentBuilder::<C> { props }.build(context)
+    }
+
+    fn get_props(&self) -> &Self::Props {
+        &self.0
+    }
+
+    fn get_mut_props(&mut self) -> &mut Self::Props {
+        &mut self.0
+    }
+}
+
+struct StructComponentHook<C: StructComponent> {
+    component: C,
+    props: C::Properties,
+    link: Link<C>,
+    /// The last output of `view`, reused when the component doesn't need to be rendered
+    view: VNode,
+}
+
+impl<C: StructComponent> Debug for StructComponentHook<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StructComponentHook")
+            .field("component", &std::any::type_name::<C>())
+            .finish()
+    }
+}
+
+impl<C: StructComponent> Hook for StructComponentHook<C> {}
+
+struct UseStructComponentBuilder<'a, C: StructComponent> {
+    props: &'a C::Properties,
+}
+
+impl<'a, C: StructComponent> HookBuilder<VNode> for UseStructComponentBuilder<'a, C> {
+    fn build(self, (fiber_id, hook_context): &mut (FiberId, &mut HookContext)) -> VNode {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            let component = C::create(self.props);
+            let link = Link {
+                messages: Rc::default(),
+                fiber_id: *fiber_id,
+            };
+            let view = component.view(&link);
+
+            hook_context.hooks.push(Box::new(StructComponentHook {
+                component,
+                props: self.props.clone(),
+                link,
+                view,
+            }));
+        }
+
+        let hook: &mut StructComponentHook<C> = hook_context.get_mut_hook(hook_position);
+
+        let mut should_render = false;
+        if hook.props != *self.props {
+            hook.props = self.props.clone();
+            should_render |= hook.component.changed(self.props);
+        }
+
+        let messages = std::mem::take(&mut *hook.link.messages.borrow_mut());
+        for msg in messages {
+            should_render |= hook.component.update(msg);
+        }
+
+        if should_render {
+            hook.view = hook.component.view(&hook.link);
+        }
+
+        hook.view.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::take_scheduled_updates;
+    use crate::{AnyComponent, ElementBuilder, Tag::Span};
+    use std::cell::Cell;
+
+    thread_local! {
+        static LINK: RefCell<Option<Link<Counter>>> = RefCell::new(None);
+        static VIEWS: Cell<usize> = Cell::new(0);
+    }
+
+    enum Msg {
+        Add(u32),
+        Ignore,
+    }
+
+    struct Counter {
+        value: u32,
+        step: u32,
+    }
+
+    impl StructComponent for Counter {
+        type Message = Msg;
+        type Properties = u32;
+
+        fn create(step: &u32) -> Self {
+            Self {
+                value: 0,
+                step: *step,
+            }
+        }
+
+        fn update(&mut self, msg: Msg) -> ShouldRender {
+            match msg {
+                Msg::Add(times) => {
+                    self.value += self.step * times;
+                    true
+                }
+                Msg::Ignore => false,
+            }
+        }
+
+        fn changed(&mut self, step: &u32) -> ShouldRender {
+            self.step = *step;
+            false
+        }
+
+        fn view(&self, link: &Link<Self>) -> VNode {
+            LINK.with(|handle| handle.replace(Some(link.clone())));
+            VIEWS.with(|views| views.set(views.get() + 1));
+
+            Span.with_child(self.value.to_string()).into()
+        }
+    }
+
+    fn send(msg: Msg) {
+        LINK.with(|handle| handle.borrow().as_ref().unwrap().send_message(msg));
+    }
+
+    #[test]
+    fn messages_and_props() {
+        let mut context = HookContext::default();
+        let mut render =
+            |step: u32| StructNode::<Counter>::new(step).render(&mut (7.into(), &mut context));
+
+        assert_eq!(render(1), Span.with_child("0").into());
+
+        send(Msg::Add(2));
+        assert_eq!(take_scheduled_updates(), vec![7.into()]);
+        assert_eq!(render(1), Span.with_child("2").into());
+
+        // The new step is stored without rendering, and it's used by the next message
+        assert_eq!(render(5), Span.with_child("2").into());
+        LINK.with(|handle| {
+            handle
+                .borrow()
+                .as_ref()
+                .unwrap()
+                .callback(|_: ()| Msg::Add(1))
+                .emit(())
+        });
+        assert_eq!(render(5), Span.with_child("7").into());
+
+        let views = VIEWS.with(Cell::get);
+        send(Msg::Ignore);
+        assert_eq!(render(5), Span.with_child("7").into());
+        assert_eq!(VIEWS.with(Cell::get), views);
+
+        take_scheduled_updates();
+    }
+}
@@ -0,0 +1,399 @@
+use crate::fiber::{FiberId, FiberTree};
+use crate::node::{Component, Node};
+use crate::Tag::Div;
+use crate::{
+    Children, ChildrenProps, ComponentProvider, ElementBuilder, Hook, HookBuilder, HookContext,
+    VNode,
+};
+use std::any::TypeId;
+use std::collections::HashSet;
+
+/// The props of a [Suspense] component
+#[derive(Debug)]
+pub struct SuspenseProps {
+    /// The node rendered while a component in `children` is suspended
+    pub fallback: VNode,
+    pub children: Children,
+}
+
+impl ChildrenProps for SuspenseProps {
+    fn children_mut(&mut self) -> &mut Children {
+        &mut self.children
+    }
+}
+
+/// A boundary for the components that are waiting on data.
+///
+/// When a component in the subtree is suspended by a [use_resource](crate::use_resource) hook,
+/// the boundary renders the `fallback` instead of its `children`.
+/// The children are only hidden, so their state is kept, and they are rendered again
+/// when every suspended component is ready.
+///
+/// The boundary adds two `<div style="display: contents">` elements around its children:
+/// the outer one holds the children and the fallback, the inner one holds the children and
+/// switches to `display: none` while the fallback is shown. They don't take part in the layout,
+/// but they are still in the DOM, so CSS selectors like `.parent > .child` don't reach the children.
+///
+/// # Example
+/// ```rust
+/// # use review::{VNode, component, children, use_resource, Resource, Suspense, SuspenseProps, ElementBuilder};
+/// # use review::Tag::{Div, P};
+/// # async fn fetch_user(id: u32) -> String { format!("user {}", id) }
+/// #[component(User)]
+/// pub fn user() -> VNode {
+///     match use_resource(|| fetch_user(7), ()) {
+///         Resource::Ready(user) => P.with_child(&*user).into(),
+///         Resource::Pending => "".into(),
+///     }
+/// }
+///
+/// #[component(App)]
+/// pub fn app() -> VNode {
+///     Div.with_child(Suspense(SuspenseProps {
+///         fallback: P.with_child("Loading...").into(),
+///         children: children!(User(())).into(),
+///     }))
+///     .into()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Suspense(pub SuspenseProps);
+
+#[derive(Debug, Default)]
+struct SuspenseHook {
+    pending: HashSet<FiberId>,
+    showing_fallback: bool,
+}
+
+impl Hook for SuspenseHook {}
+
+struct UseSuspenseBuilder;
+
+impl HookBuilder<bool> for UseSuspenseBuilder {
+    fn build(self, (_, hook_context): &mut (FiberId, &mut HookContext)) -> bool {
+        let hook_position = hook_context.counter;
+        hook_context.counter += 1;
+        if hook_position >= hook_context.hooks.len() {
+            hook_context.hooks.push(Box::new(SuspenseHook::default()));
+        }
+
+        let hook: &mut SuspenseHook = hook_context.get_mut_hook(hook_position);
+        hook.showing_fallback = !hook.pending.is_empty();
+
+        hook.showing_fallback
+    }
+}
+
+impl ComponentProvider for Suspense {
+    type Props = SuspenseProps;
+
+    fn render(context: &mut (FiberId, &mut HookContext), props: &Self::Props) -> VNode {
+        let showing_fallback = UseSuspenseBuilder.build(context);
+
+        let content = Div
+            .with_attribute(
+                "style",
+                if showing_fallback {
+                    "display: none"
+                } else {
+                    "display: contents"
+                },
+            )
+            .with_children(props.children.to_vec());
+        let boundary = Div
+            .with_attribute("style", "display: contents")
+            .with_child(content);
+
+        if showing_fallback {
+            boundary.with_child(props.fallback.clone()).into()
+        } else {
+            boundary.into()
+        }
+    }
+
+    fn get_props(&self) -> &Self::Props {
+        &self.0
+    }
+
+    fn get_mut_props(&mut self) -> &mut Self::Props {
+        &mut self.0
+    }
+}
+
+fn get_suspense_hook(id: FiberId, fiber_tree: &mut FiberTree) -> Option<&mut SuspenseHook> {
+    match fiber_tree
+        .get_mut(id)
+        .map(|fiber_node| &mut fiber_node.node)
+    {
+        Some(Node::Component(Component {
+            hook_context,
+            function,
+        })) if function.get_type() == TypeId::of::<Suspense>() => hook_context
+            .hooks
+            .first_mut()
+            .and_then(|hook| hook.downcast_mut::<SuspenseHook>()),
+        _ => None,
+    }
+}
+
+fn get_hook_context(id: FiberId, fiber_tree: &mut FiberTree) -> Option<&mut HookContext> {
+    match fiber_tree
+        .get_mut(id)
+        .map(|fiber_node| &mut fiber_node.node)
+    {
+        Some(Node::Component(Component { hook_context, .. })) => Some(hook_context),
+        _ => None,
+    }
+}
+
+fn find_boundary(id: FiberId, fiber_tree: &mut FiberTree) -> Option<FiberId> {
+    let mut parent_id = fiber_tree.get(id).and_then(|fiber_node| fiber_node.parent);
+    while let Some(some_parent_id) = parent_id {
+        if get_suspense_hook(some_parent_id, fiber_tree).is_some() {
+            return Some(some_parent_id);
+        }
+        parent_id = fiber_tree
+            .get(some_parent_id)
+            .and_then(|fiber_node| fiber_node.parent);
+    }
+
+    None
+}
+
+/// Registers the suspended component in its nearest boundary.
+///
+/// Returns the boundary when it has to be rendered again to show its fallback.
+pub(crate) fn suspend(id: FiberId, fiber_tree: &mut FiberTree) -> Option<FiberId> {
+    let boundary_id = match find_boundary(id, fiber_tree) {
+        Some(boundary_id) => boundary_id,
+        None => {
+            log::warn!("a component is suspended outside of a Suspense boundary");
+            return None;
+        }
+    };
+
+    if let Some(hook_context) = get_hook_context(id, fiber_tree) {
+        hook_context.suspense_boundary = Some(boundary_id);
+    }
+
+    let hook = get_suspense_hook(boundary_id, fiber_tree)?;
+    hook.pending.insert(id);
+
+    if hook.showing_fallback {
+        None
+    } else {
+        Some(boundary_id)
+    }
+}
+
+/// Removes a component that is no longer suspended from its boundary.
+///
+/// Returns the boundary when it has to be rendered again to show its children.
+pub(crate) fn resume(id: FiberId, fiber_tree: &mut FiberTree) -> Option<FiberId> {
+    let boundary_id = get_hook_context(id, fiber_tree)?.suspense_boundary.take()?;
+    let hook = get_suspense_hook(boundary_id, fiber_tree)?;
+
+    if hook.pending.remove(&id) && hook.pending.is_empty() && hook.showing_fallback {
+        Some(boundary_id)
+    } else {
+        None
+    }
+}
+
+fn resume_all(id: FiberId, fiber_tree: &mut FiberTree, boundaries: &mut Vec<FiberId>) {
+    // Children are resumed before their parents
+    let mut current_id = id;
+    loop {
+        while let Some(child_id) = fiber_tree.get(current_id).and_then(|node| node.child) {
+            current_id = child_id;
+        }
+
+        loop {
+            if let Some(boundary_id) = resume(current_id, fiber_tree) {
+                boundaries.push(boundary_id);
+            }
+
+            if current_id == id {
+                return;
+            }
+
+            let fiber_node = fiber_tree.get(current_id);
+            match (
+                fiber_node.and_then(|node| node.sibling),
+                fiber_node.and_then(|node| node.parent),
+            ) {
+                (Some(sibling_id), _) => {
+                    current_id = sibling_id;
+                    break;
+                }
+                (None, Some(parent_id)) => current_id = parent_id,
+                (None, None) => return,
+            }
+        }
+    }
+}
+
+/// Like [resume] but for every component in a subtree that is going to be removed.
+///
+/// The boundaries that are removed with the subtree are never returned.
+pub(crate) fn resume_subtree(id: FiberId, fiber_tree: &mut FiberTree) -> Option<FiberId> {
+    let mut boundaries = Vec::new();
+    resume_all(id, fiber_tree, &mut boundaries);
+
+    boundaries
+        .into_iter()
+        .find(|boundary_id| *boundary_id != id && !fiber_tree.is_ancestor(id, *boundary_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{commit_work, work_loop, App};
+    use crate::node::Element;
+    use crate::run_until_stalled;
+    use crate::scheduler::{start_next_update, take_scheduled_updates};
+    use crate::Events;
+    use crate::Tag;
+    use futures::channel::oneshot;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use crate as review;
+    use review::*;
+
+    thread_local! {
+        static RECEIVER: RefCell<Option<oneshot::Receiver<String>>> = RefCell::new(None);
+    }
+
+    #[component(Pending)]
+    fn pending() -> VNode {
+        let resource = use_resource(
+            || async {
+                RECEIVER
+                    .with(|receiver| receiver.borrow_mut().take())
+                    .unwrap()
+                    .await
+                    .unwrap()
+            },
+            (),
+        );
+
+        match resource {
+            Resource::Ready(text) => (*text).clone().into(),
+            Resource::Pending => "".into(),
+        }
+    }
+
+    fn boundary_state(app: &mut App, boundary_id: FiberId) -> (usize, bool) {
+        let hook = get_suspense_hook(boundary_id, &mut app.fiber_tree).unwrap();
+
+        (hook.pending.len(), hook.showing_fallback)
+    }
+
+    /// Mounts a boundary around a suspended component, whose resource is completed by the sender
+    fn mount_suspended() -> (App, FiberId, oneshot::Sender<String>) {
+        let (sender, receiver) = oneshot::channel();
+        RECEIVER.with(|r| r.replace(Some(receiver)));
+
+        let mut fiber_tree = FiberTree::default();
+        let root_id = fiber_tree.new_node(Node::Element(Element {
+            dom: None,
+            tag: Tag::Empty,
+            attributes: HashMap::with_capacity(0),
+            events: Events(HashMap::with_capacity(0)),
+            unprocessed_children: vec![Suspense(SuspenseProps {
+                fallback: "loading".into(),
+                children: children!(Pending(())).into(),
+            })
+            .into()],
+        }));
+        let mut app = App {
+            fiber_tree,
+            next_unit_of_work: Some(root_id),
+            wip_root: Some(root_id),
+            effects: Vec::new(),
+            rendered: Vec::new(),
+            document: None,
+        };
+
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+
+        (app, root_id, sender)
+    }
+
+    #[test]
+    fn show_fallback_while_suspended() {
+        let (mut app, root_id, sender) = mount_suspended();
+
+        let boundary_id = children(&app, root_id)[0];
+        let wrapper_id = children(&app, boundary_id)[0];
+        assert_eq!(boundary_state(&mut app, boundary_id), (1, true));
+        assert_eq!(children(&app, wrapper_id).len(), 2);
+        assert_eq!(text(&app, children(&app, wrapper_id)[1]), "loading");
+
+        let content_id = children(&app, wrapper_id)[0];
+        let pending_id = children(&app, content_id)[0];
+
+        // The render that suspended the component doesn't schedule anything else
+        assert!(take_scheduled_updates().is_empty());
+
+        sender.send("ready".to_owned()).unwrap();
+        run_until_stalled();
+
+        // The resolved resource schedules the suspended component,
+        // whose render restarts from the boundary to show the children again
+        start_next_update(&mut app);
+        assert_eq!(app.wip_root, Some(pending_id));
+        work_loop(&mut app, || true);
+        commit_work(&mut app, || true);
+        assert!(take_scheduled_updates().is_empty());
+
+        assert_eq!(boundary_state(&mut app, boundary_id), (0, false));
+        assert_eq!(children(&app, wrapper_id), vec![content_id]);
+        assert_eq!(children(&app, content_id), vec![pending_id]);
+        assert_eq!(text(&app, children(&app, pending_id)[0]), "ready");
+    }
+
+    #[test]
+    fn resume_removed_subtree() {
+        let (mut app, root_id, _sender) = mount_suspended();
+        take_scheduled_updates();
+
+        let boundary_id = children(&app, root_id)[0];
+        let wrapper_id = children(&app, boundary_id)[0];
+        let content_id = children(&app, wrapper_id)[0];
+
+        // Removing the suspended component resumes it, so its boundary shows the children again
+        assert_eq!(
+            resume_subtree(content_id, &mut app.fiber_tree),
+            Some(boundary_id)
+        );
+        assert_eq!(boundary_state(&mut app, boundary_id), (0, true));
+
+        // A boundary removed with the subtree is never returned
+        let (mut app, root_id, _sender) = mount_suspended();
+        take_scheduled_updates();
+        let boundary_id = children(&app, root_id)[0];
+        assert_eq!(resume_subtree(boundary_id, &mut app.fiber_tree), None);
+        assert_eq!(boundary_state(&mut app, boundary_id), (0, true));
+    }
+
+    fn children(app: &App, id: FiberId) -> Vec<FiberId> {
+        let mut children = Vec::new();
+        let mut child_id = app.fiber_tree.get(id).and_then(|n| n.child);
+        while let Some(some_child_id) = child_id {
+            children.push(some_child_id);
+            child_id = app.fiber_tree.get(some_child_id).and_then(|n| n.sibling);
+        }
+
+        children
+    }
+
+    fn text(app: &App, id: FiberId) -> &str {
+        match app.fiber_tree.get(id).map(|n| &n.node) {
+            Some(Node::Text(text)) => &text.text,
+            _ => panic!("not a text node"),
+        }
+    }
+}
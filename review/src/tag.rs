@@ -1,7 +1,23 @@
-/// Rappresent all possible tag used in reView
-#[derive(strum_macros::AsRefStr, strum_macros::ToString, PartialEq, Clone, Copy, Debug)]
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// Rappresent all possible tag used in reView.
+///
+/// Elements that are not listed, like the custom elements of web components,
+/// are created with [Tag::custom].
+/// A listed tag can also be parsed from its lowercase name with [str::parse].
+///
+/// `Tag` is `Clone` but not `Copy`, since a custom tag owns its name.
+#[derive(strum_macros::EnumDiscriminants, strum_macros::EnumString, PartialEq, Clone, Debug)]
 #[strum(serialize_all = "lowercase")]
+#[strum_discriminants(
+    name(TagName),
+    vis(pub(crate)),
+    derive(strum_macros::IntoStaticStr),
+    strum(serialize_all = "lowercase")
+)]
 pub enum Tag {
+    #[strum(disabled)]
     Empty,
     A,
     Abbr,
@@ -90,8 +106,10 @@ pub enum Tag {
     S,
     Samp,
     Script,
+    Search,
     Section,
     Select,
+    Slot,
     Small,
     Source,
     Span,
@@ -103,6 +121,7 @@ pub enum Tag {
     Table,
     Tbody,
     Td,
+    Template,
     Textarea,
     Tfoot,
     Th,
@@ -116,4 +135,83 @@ pub enum Tag {
     Var,
     Video,
     Wbr,
+    /// An element with any other name, like `my-widget`.
+    /// Prefer [Tag::custom], that returns the listed tag when there is one with the same name
+    #[strum(disabled)]
+    Custom(Cow<'static, str>),
+}
+
+impl Tag {
+    /// Creates the tag of an element that is not listed, like a custom element.
+    ///
+    /// The name of a listed element returns its variant, so `Tag::custom("div")` is [Tag::Div].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use review::{ElementBuilder, Tag, VNode};
+    /// let button: VNode = Tag::custom("sl-button")
+    ///     .with_attribute("variant", "primary")
+    ///     .with_child("Save")
+    ///     .into();
+    ///
+    /// assert_eq!(Tag::custom("sl-button").to_string(), "sl-button");
+    /// assert_eq!(Tag::custom("div"), Tag::Div);
+    /// ```
+    pub fn custom(name: impl Into<Cow<'static, str>>) -> Self {
+        let name = name.into();
+
+        name.parse().unwrap_or(Tag::Custom(name))
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        match self {
+            Tag::Custom(name) => name,
+            tag => TagName::from(tag).into(),
+        }
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(Tag::Div.as_ref(), "div");
+        assert_eq!(Tag::Blockquote.to_string(), "blockquote");
+        assert_eq!(Tag::Template.as_ref(), "template");
+        assert_eq!(Tag::Empty.as_ref(), "empty");
+        assert_eq!(Tag::custom("my-widget").as_ref(), "my-widget");
+        assert_eq!(
+            Tag::custom(String::from("sl-button")).to_string(),
+            "sl-button"
+        );
+    }
+
+    #[test]
+    fn custom_equality() {
+        assert_eq!(
+            Tag::custom("my-widget"),
+            Tag::custom(String::from("my-widget"))
+        );
+        assert_ne!(Tag::custom("my-widget"), Tag::custom("my-other-widget"));
+        assert_eq!(Tag::custom("div"), Tag::Div);
+        assert_eq!(Tag::custom(String::from("blockquote")), Tag::Blockquote);
+    }
+
+    #[test]
+    fn parse_listed_names_only() {
+        assert_eq!("template".parse(), Ok(Tag::Template));
+        assert!("empty".parse::<Tag>().is_err());
+        assert!("custom".parse::<Tag>().is_err());
+        assert_eq!(Tag::custom("empty"), Tag::Custom("empty".into()));
+    }
 }
@@ -4,9 +4,8 @@ use wasm_bindgen::JsCast;
 /// Initialize the logger with the specified minimum log [log::Level]
 pub fn init_logger(level: log::Level) {
     console_log::init_with_level(level).expect("error initializing log");
-    if cfg!(feature = "log-panics") {
-        log_panics::init();
-    }
+    #[cfg(feature = "log-panics")]
+    log_panics::init();
 }
 
 pub(crate) fn request_animation_frame(f: &Closure<dyn FnMut()>) {
@@ -15,3 +14,36 @@ pub(crate) fn request_animation_frame(f: &Closure<dyn FnMut()>) {
         .request_animation_frame(f.as_ref().unchecked_ref())
         .expect("should register `requestAnimationFrame` OK");
 }
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn spawn_local<F: std::future::Future<Output = ()> + 'static>(future: F) {
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static LOCAL_POOL: std::cell::RefCell<futures::executor::LocalPool> =
+        std::cell::RefCell::new(futures::executor::LocalPool::new());
+    static LOCAL_SPAWNER: futures::executor::LocalSpawner =
+        LOCAL_POOL.with(|pool| pool.borrow().spawner());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_local<F: std::future::Future<Output = ()> + 'static>(future: F) {
+    use futures::task::LocalSpawnExt;
+
+    LOCAL_SPAWNER.with(|spawner| {
+        spawner
+            .spawn_local(future)
+            .expect("error spawning local future")
+    });
+}
+
+/// Runs all the futures spawned by reView hooks until none of them can make progress.
+///
+/// On `wasm32` targets the futures are driven by the browser event loop,
+/// on the other targets this function can be used to drive them (e.g. in unit tests).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_until_stalled() {
+    LOCAL_POOL.with(|pool| pool.borrow_mut().run_until_stalled());
+}
@@ -231,7 +231,7 @@ pub trait ElementBuilder {
     /// let mut velement = Div.with_event(OnClick, callback!(move || log::info!("hello!!")));
     /// velement.with_event(OnMouseEnter, callback!(move || log::info!("mouseEnter!!")));
     /// ```
-    fn with_event(self, event: EventType, callback: Event) -> VElement;
+    fn with_event(self, event: EventType, callback: impl Into<Event>) -> VElement;
 }
 
 impl ElementBuilder for VElement {
@@ -264,8 +264,8 @@ impl ElementBuilder for VElement {
         self
     }
 
-    fn with_event(mut self, event: EventType, callback: Event) -> VElement {
-        self.events.0.insert(event, callback);
+    fn with_event(mut self, event: EventType, callback: impl Into<Event>) -> VElement {
+        self.events.0.insert(event, callback.into());
 
         self
     }
@@ -317,11 +317,14 @@ impl ElementBuilder for Tag {
         element
     }
 
-    fn with_event(self, event: EventType, callback: Event) -> VElement {
+    fn with_event(self, event: EventType, callback: impl Into<Event>) -> VElement {
         VElement {
             tag: self,
             attributes: HashMap::with_capacity(0),
-            events: Events(HashMap::<EventType, Event>::from([(event, callback)])),
+            events: Events(HashMap::<EventType, Event>::from([(
+                event,
+                callback.into(),
+            )])),
             children: Vec::with_capacity(0),
         }
     }
@@ -336,38 +339,88 @@ impl ElementBuilder for Tag {
 /// # use review::EventType::OnClick;
 /// Button.with_event(OnClick, callback!(move || log::info!("hello from js on click event")));
 /// ```
+///
+/// The closure can take any number of parameters. Their types are used to create the js closure,
+/// and a parameter without a type receives a `JsValue`
+/// ```rust,no_run
+/// # use review::{callback, log, ElementBuilder, JsValue};
+/// # use review::Tag::Button;
+/// # use review::EventType::{OnClick, OnFocus};
+/// # use web_sys::MouseEvent;
+/// Button
+///     .with_event(OnClick, callback!(|event: MouseEvent| log::info!("clicked at {}", event.client_x())))
+///     .with_event(OnFocus, callback!(|event| log::info!("{:?}", event)));
+/// ```
+///
+/// The variables in square brackets before the closure are cloned, so a `move` closure
+/// takes the clones instead of the originals
+/// ```rust,no_run
+/// # use review::{callback, component, html, use_state, VNode};
+/// # use web_sys::MouseEvent;
+/// #[component(Counter)]
+/// pub fn counter() -> VNode {
+///     let counter = use_state(0);
+///     let step = use_state(1);
+///
+///     html! {
+///         <div>
+///             <button on_click={callback!([counter, step] move |_: MouseEvent| counter.update(|prev| prev + *step))}>
+///                 "Increment"
+///             </button>
+///             <button on_click={callback!([step] move || step.set(*step + 1))}>"Increase the step"</button>
+///             {format!("{}", *counter)}
+///         </div>
+///     }
+/// }
+/// ```
+///
+/// With `Callback,` before the closure it creates a [Callback](crate::Callback) instead, that can be
+/// passed to the children of a component and attached as an event as well
+/// ```rust
+/// # use review::{callback, log, use_state, Callback};
+/// let on_select: Callback<usize> = callback!(Callback, |index: usize| log::info!("{}", index));
+/// ```
 #[macro_export]
 macro_rules! callback {
-    (|| $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(
-            ::std::boxed::Box::new(|| $body) as ::std::boxed::Box<dyn Fn()>
-        ));
+    (Callback, [$( $clone:ident ),* $(,)?] $closure:expr) => {{
+        $( let $clone = ::std::clone::Clone::clone(&$clone); )*
+        $crate::Callback::from($closure)
+    }};
+    (Callback, $closure:expr) => {
+        $crate::Callback::from($closure)
     };
-    (move || $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(
-            ::std::boxed::Box::new(move || $body) as ::std::boxed::Box<dyn Fn()>,
-        ));
+    ([$( $clone:ident ),* $(,)?] $( $closure:tt )+) => {{
+        $( let $clone = ::std::clone::Clone::clone(&$clone); )*
+        $crate::callback!(@event $( $closure )+)
+    }};
+    (@event || $body:expr) => {
+        $crate::callback!(@wrap ::std::boxed::Box::new(|| $body) as ::std::boxed::Box<dyn Fn()>)
     };
-    (|$args:ident| $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(
-            ::std::boxed::Box::new(|$args| $body) as ::std::boxed::Box<dyn Fn(_)>,
-        ));
+    (@event move || $body:expr) => {
+        $crate::callback!(@wrap ::std::boxed::Box::new(move || $body) as ::std::boxed::Box<dyn Fn()>)
     };
-    (move |$args:ident| $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(
-            ::std::boxed::Box::new(move |$args| $body) as ::std::boxed::Box<dyn Fn(_)>,
-        ));
+    (@event |$( $arg:tt $(: $arg_type:ty)? ),*| $body:expr) => {
+        $crate::callback!(@wrap ::std::boxed::Box::new(
+            |$( $arg: $crate::callback!(@type $arg $($arg_type)?) ),*| $body
+        ) as ::std::boxed::Box<dyn Fn($( $crate::callback!(@type $arg $($arg_type)?) ),*)>)
     };
-    (|$args:ident : $args_type:ty | $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(
-            ::std::boxed::Box::new(|$args: $args_type| $body) as ::std::boxed::Box<dyn Fn(_)>,
-        ));
+    (@event move |$( $arg:tt $(: $arg_type:ty)? ),*| $body:expr) => {
+        $crate::callback!(@wrap ::std::boxed::Box::new(
+            move |$( $arg: $crate::callback!(@type $arg $($arg_type)?) ),*| $body
+        ) as ::std::boxed::Box<dyn Fn($( $crate::callback!(@type $arg $($arg_type)?) ),*)>)
     };
-    (move |$args:ident : $args_type:ty| $body:expr) => {
-        ::std::rc::Rc::new(::review::Closure::wrap(::std::boxed::Box::new(
-            move |$args: $args_type| $body,
-        )
-            as ::std::boxed::Box<dyn Fn(_)>));
+    (@type $arg:tt) => {
+        $crate::JsValue
+    };
+    (@type $arg:tt $arg_type:ty) => {
+        $arg_type
+    };
+    (@wrap $closure:expr) => {{
+        let event: $crate::Event = ::std::rc::Rc::new($crate::Closure::wrap($closure));
+        event
+    }};
+    ($( $closure:tt )+) => {
+        $crate::callback!(@event $( $closure )+)
     };
 }
 
@@ -390,6 +443,7 @@ macro_rules! children {
 mod tests {
 
     use super::*;
+    use crate as review;
     use crate::Events;
     use crate::Tag::*;
     use crate::VElement;
@@ -457,4 +511,120 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn html_elements() {
+        let status = "ready";
+        let vdom = review::html! {
+            <div class="game" data-id={7} hidden>
+                <h1>"Title"</h1>
+                {status}
+                <br />
+            </div>
+        };
+
+        assert_eq!(
+            vdom,
+            Div.with_attributes(vec![("class", "game"), ("data-id", "7"), ("hidden", "")])
+                .with_children(children!(H1.with_child("Title"), "ready", Br))
+                .into()
+        );
+    }
+
+    #[test]
+    fn html_iterators() {
+        let rows = ["first", "second"];
+        let vdom = review::html! {
+            <ul>
+                <li>"header"</li>
+                {for rows.iter().map(|row| review::html! { <li>{*row}</li> })}
+                {for vec!["footer"]}
+            </ul>
+        };
+
+        assert_eq!(
+            vdom,
+            Ul.with_children(children!(
+                Li.with_child("header"),
+                Li.with_child("first"),
+                Li.with_child("second"),
+                "footer"
+            ))
+            .into()
+        );
+    }
+
+    #[test]
+    fn html_custom_elements() {
+        let vdom = review::html! {
+            <sl-card class="profile">
+                <template>
+                    <sl-button variant="primary">"Save"</sl-button>
+                </template>
+            </sl-card>
+        };
+
+        assert_eq!(
+            vdom,
+            Tag::custom("sl-card")
+                .with_attribute("class", "profile")
+                .with_child(
+                    Template.with_child(
+                        Tag::custom("sl-button")
+                            .with_attribute("variant", "primary")
+                            .with_child("Save")
+                    )
+                )
+                .into()
+        );
+    }
+
+    #[derive(Debug, Clone, review::Properties)]
+    struct LabelProps {
+        #[prop(into, default)]
+        text: String,
+        #[prop(default)]
+        bold: bool,
+    }
+
+    #[review::component(Label)]
+    fn label(props: &LabelProps) -> VNode {
+        if props.bold {
+            B.with_child(&props.text).into()
+        } else {
+            Span.with_child(&props.text).into()
+        }
+    }
+
+    fn render(vnode: VNode) -> VNode {
+        match vnode {
+            VNode::Component(component) => {
+                component.render(&mut (0.into(), &mut HookContext::default()))
+            }
+            _ => panic!("expected a component"),
+        }
+    }
+
+    #[test]
+    fn html_components() {
+        let props = LabelProps {
+            text: "base".to_owned(),
+            bold: true,
+        };
+
+        let vdom = review::html! { <Label text={"label".to_owned()} bold={false} /> };
+        assert_eq!(render(vdom), Span.with_child("label").into());
+
+        let vdom = review::html! { <Label text="label" bold={true} /> };
+        assert_eq!(render(vdom), B.with_child("label").into());
+
+        let vdom = review::html! { <Label text={"label".to_owned()} ..{props.clone()} /> };
+        assert_eq!(render(vdom), B.with_child("label").into());
+
+        let vdom = review::html! { <Label ..props /> };
+        assert_eq!(render(vdom), B.with_child("base").into());
+
+        let vdom = review::html! { <Label /> };
+        assert_eq!(render(vdom), Span.with_child("").into());
+    }
 }
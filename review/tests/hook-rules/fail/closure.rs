@@ -0,0 +1,15 @@
+#![allow(unused_imports)]
+
+use review::{component, use_state, VNode};
+
+#[component]
+fn counter() -> VNode {
+    let create = || {
+        let _ = use_state(0);
+    };
+    create();
+
+    "".into()
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![allow(unused_imports)]
+
+use review::{component, use_state, VNode};
+
+#[component]
+fn counter(visible: bool) -> VNode {
+    if visible {
+        let _ = use_state(0);
+    }
+
+    "".into()
+}
+
+fn main() {}
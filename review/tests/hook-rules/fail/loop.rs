@@ -0,0 +1,25 @@
+#![allow(unused_imports)]
+
+use review::{component, use_state, VNode};
+
+#[component]
+fn counters(count: usize) -> VNode {
+    for _ in 0..count {
+        let _ = use_state(0);
+    }
+
+    let mut index = 0;
+    while index < count {
+        let _ = use_state(0);
+        index += 1;
+    }
+
+    loop {
+        let _ = use_state(0);
+        break;
+    }
+
+    "".into()
+}
+
+fn main() {}
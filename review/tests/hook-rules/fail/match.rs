@@ -0,0 +1,17 @@
+#![allow(unused_imports)]
+
+use review::{component, use_state, VNode};
+
+#[component]
+fn counter(step: Option<u32>) -> VNode {
+    match step {
+        Some(step) => {
+            let _ = use_state(step);
+        }
+        None => {}
+    }
+
+    "".into()
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(unused_imports)]
+
+use review::{component, use_state, VNode};
+
+#[component]
+fn counter(visible: bool) -> VNode {
+    if !visible {
+        return "".into();
+    }
+
+    let _ = use_state(0);
+
+    "".into()
+}
+
+fn main() {}
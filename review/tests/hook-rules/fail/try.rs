@@ -0,0 +1,12 @@
+#![allow(unused_imports)]
+
+use review::{hook, use_state, UseStateHandle};
+
+#[hook]
+fn use_parsed(value: &str) -> Option<UseStateHandle<u32>> {
+    let initial = value.parse().ok()?;
+
+    Some(use_state(initial))
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![allow(unused_imports)]
+
+use review::{hook, use_state, UseStateHandle};
+
+#[hook]
+fn use_parsed(value: &str) -> Option<UseStateHandle<u32>> {
+    Some(use_state(value.parse().ok()?))
+}
+
+fn main() {}
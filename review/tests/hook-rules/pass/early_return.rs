@@ -0,0 +1,25 @@
+use review::{component, hook, use_state, UseStateHandle, VNode};
+
+#[hook]
+fn use_parsed(value: &str) -> Option<UseStateHandle<u32>> {
+    let state = use_state(0);
+    let parse = |value: &str| -> Option<u32> { Some(value.parse().ok()?) };
+    state.set(parse(value)?);
+
+    Some(state)
+}
+
+#[component]
+fn counter(visible: bool) -> VNode {
+    let counter = use_state(0);
+    let label = async { Some(format!("{}", Some(1)?)) };
+    drop(label);
+
+    if !visible {
+        return "".into();
+    }
+
+    format!("{}", *counter).into()
+}
+
+fn main() {}
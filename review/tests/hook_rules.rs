@@ -0,0 +1,6 @@
+#[test]
+fn hook_rules() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/hook-rules/fail/*.rs");
+    cases.pass("tests/hook-rules/pass/*.rs");
+}
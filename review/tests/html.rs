@@ -0,0 +1,6 @@
+#[test]
+fn html_errors() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/html/fail/*.rs");
+    cases.pass("tests/html/pass/*.rs");
+}
@@ -0,0 +1,13 @@
+#![allow(unused_imports)]
+
+use review::{html, VNode};
+
+fn view(name: String) -> VNode {
+    html! {
+        <div>
+            {"Hello" name}
+        </div>
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![allow(unused_imports)]
+
+use review::{html, VNode};
+
+fn view() -> VNode {
+    html! {
+        <div>
+            <p>"text"</span>
+        </div>
+    }
+}
+
+fn main() {}
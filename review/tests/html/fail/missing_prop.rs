@@ -0,0 +1,14 @@
+#![allow(unused_imports)]
+
+use review::{component, html, VNode};
+
+#[component]
+fn greeting(name: String, count: u32) -> VNode {
+    html! { <p>{format!("{} {}", name, count)}</p> }
+}
+
+fn view() -> VNode {
+    html! { <Greeting count={1} /> }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![allow(unused_imports)]
+
+use review::{html, VNode};
+
+fn view() -> VNode {
+    html! {
+        <div>
+            <p>"text"</p>
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![allow(unused_imports)]
+
+use review::{component, html, VNode};
+
+#[component]
+fn greeting(name: String) -> VNode {
+    html! { <p>{name}</p> }
+}
+
+fn view() -> VNode {
+    html! { <Greeting name={String::from("reView")} color="red" /> }
+}
+
+fn main() {}
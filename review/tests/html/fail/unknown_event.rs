@@ -0,0 +1,9 @@
+#![allow(unused_imports)]
+
+use review::{callback, html, VNode};
+
+fn view() -> VNode {
+    html! { <button on_tap={callback!(|| {})}>"Tap"</button> }
+}
+
+fn main() {}
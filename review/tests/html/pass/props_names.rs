@@ -0,0 +1,24 @@
+use review::{component, html, Properties, VNode};
+
+#[derive(Properties)]
+pub struct Card {
+    title: String,
+}
+
+#[derive(Properties)]
+pub struct CardProps {
+    title: String,
+}
+
+#[component(Panel)]
+fn panel(props: &CardProps) -> VNode {
+    props.title.clone().into()
+}
+
+fn main() {
+    let card = Card::builder().title("card".to_owned()).build();
+    let props = CardProps::builder().title(card.title).build();
+    drop(props);
+
+    let _ = html! { <Panel title={"panel".to_owned()} /> };
+}